@@ -0,0 +1,152 @@
+use std::io;
+
+use bytes::BufMut;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt};
+
+use crate::bytes::reader::{too_long, truncated};
+
+/// the async counterpart of `ByteReader`
+pub struct AsyncByteReader<T: AsyncRead + Unpin> {
+    inner: T,
+}
+
+impl<T: AsyncRead + Unpin> AsyncByteReader<T> {
+    pub fn new(inner: T) -> Self {
+        return Self { inner };
+    }
+
+    pub async fn read<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf: [u8; N] = [0; N];
+        self.read_at_most(&mut buf).await?;
+        return Ok(buf);
+    }
+
+    pub async fn read_utf8<const N: usize>(&mut self) -> io::Result<String> {
+        let bytes = self.read::<N>().await?;
+        return Ok(String::from_utf8_lossy(&bytes).to_string());
+    }
+
+    pub async fn read_at_most(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut count = 0;
+
+        if buf.is_empty() {
+            return Ok(count);
+        }
+
+        loop {
+            let size = self.inner.read(&mut buf[count..]).await?;
+            count += size;
+
+            if count == buf.len() || size == 0 {
+                break;
+            }
+        }
+
+        return Ok(count);
+    }
+
+    /// read up to `max` bytes, growing the buffer as they
+    /// arrive rather than allocating all of them up front
+    pub async fn read_to_vec_at_most(&mut self, max: usize) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        (&mut self.inner)
+            .take(max as u64)
+            .read_to_end(&mut data)
+            .await?;
+        return Ok(data);
+    }
+
+    pub async fn read_until<const N: usize>(&mut self, seq: &[u8; N]) -> io::Result<Vec<u8>> {
+        let mut data = bytes::BytesMut::new();
+        let mut buf = [0; 1];
+
+        loop {
+            let size = self.read_at_most(&mut buf).await?;
+
+            if size == 0 {
+                break;
+            }
+
+            data.put_slice(&buf);
+
+            if data.ends_with(seq) {
+                data.truncate(data.len() - seq.len());
+                break;
+            }
+        }
+
+        return Ok(data.to_vec());
+    }
+
+    pub async fn read_utf8_until<const N: usize>(&mut self, seq: &[u8; N]) -> io::Result<String> {
+        let bytes = self.read_until(seq).await?;
+        return Ok(String::from_utf8_lossy(&bytes).to_string());
+    }
+
+    pub async fn read_until_exclusive<const N: usize>(
+        &mut self,
+        seq: &[u8; N],
+    ) -> io::Result<Vec<u8>> {
+        return self.read_until(seq).await;
+    }
+
+    pub async fn read_utf8_until_exclusive<const N: usize>(
+        &mut self,
+        seq: &[u8; N],
+    ) -> io::Result<String> {
+        let bytes = self.read_until_exclusive(seq).await?;
+        return Ok(String::from_utf8_lossy(&bytes).to_string());
+    }
+}
+
+impl<T: AsyncBufRead + Unpin> AsyncByteReader<T> {
+    /// read until `seq` like `read_until_exclusive`, failing with
+    /// `InvalidData` once more than `max` bytes come before it,
+    /// searching the buffered bytes for `seq` rather than reading
+    /// them one at a time, `None` when the stream is closed before
+    /// any byte and `UnexpectedEof` when it is cut off before `seq`
+    pub async fn read_until_exclusive_at_most<const N: usize>(
+        &mut self,
+        seq: &[u8; N],
+        max: usize,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let mut data = Vec::new();
+
+        loop {
+            let buf = self.inner.fill_buf().await?;
+            let size = buf.len();
+
+            if size == 0 {
+                return match data.is_empty() {
+                    true => Ok(None),
+                    false => Err(truncated()),
+                };
+            }
+
+            // `seq` may start in the bytes of the last fill
+            let from = data.len().saturating_sub(N - 1);
+            let before = data.len();
+            data.extend_from_slice(buf);
+
+            if let Some(i) = data[from..].windows(N).position(|v| v == seq) {
+                let end = from + i;
+                self.inner.consume(end + N - before);
+                data.truncate(end);
+
+                if end > max {
+                    return Err(too_long(max));
+                }
+
+                break;
+            }
+
+            self.inner.consume(size);
+
+            if data.len() > max.saturating_add(N - 1) {
+                return Err(too_long(max));
+            }
+        }
+
+        return Ok(Some(data));
+    }
+}
@@ -3,3 +3,8 @@ pub use reader::*;
 
 mod scanner;
 pub use scanner::*;
+
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "tokio")]
+pub use async_reader::*;
@@ -1,4 +1,7 @@
-use std::{io, net};
+use std::{
+    io::{self, Read},
+    net,
+};
 
 use bytes::BufMut;
 
@@ -26,8 +29,12 @@ impl<T: io::Read> ByteReader<T> {
     pub fn read_at_most(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let mut count = 0;
 
+        if buf.is_empty() {
+            return Ok(count);
+        }
+
         loop {
-            let size = self.inner.read(buf)?;
+            let size = self.inner.read(&mut buf[count..])?;
             count += size;
 
             if count == buf.len() || size == 0 {
@@ -38,18 +45,27 @@ impl<T: io::Read> ByteReader<T> {
         return Ok(count);
     }
 
+    /// read up to `max` bytes, growing the buffer as they
+    /// arrive rather than allocating all of them up front
+    pub fn read_to_vec_at_most(&mut self, max: usize) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        (&mut self.inner).take(max as u64).read_to_end(&mut data)?;
+        return Ok(data);
+    }
+
     pub fn read_until<const N: usize>(&mut self, seq: &[u8; N]) -> io::Result<Vec<u8>> {
         let mut data = bytes::BytesMut::new();
         let mut buf = [0; 1];
 
         loop {
             let size = self.read_at_most(&mut buf)?;
-            data.put_slice(&buf);
 
             if size == 0 {
                 break;
             }
 
+            data.put_slice(&buf);
+
             if data.ends_with(seq) {
                 data.truncate(data.len() - seq.len());
                 break;
@@ -65,32 +81,67 @@ impl<T: io::Read> ByteReader<T> {
     }
 
     pub fn read_until_exclusive<const N: usize>(&mut self, seq: &[u8; N]) -> io::Result<Vec<u8>> {
-        let mut data = bytes::BytesMut::new();
-        let mut buf = [0; 1];
+        return self.read_until(seq);
+    }
+
+    pub fn read_utf8_until_exclusive<const N: usize>(
+        &mut self,
+        seq: &[u8; N],
+    ) -> io::Result<String> {
+        let bytes = self.read_until_exclusive(seq)?;
+        return Ok(String::from_utf8_lossy(&bytes).to_string());
+    }
+}
+
+impl<T: io::BufRead> ByteReader<T> {
+    /// read until `seq` like `read_until_exclusive`, failing with
+    /// `InvalidData` once more than `max` bytes come before it,
+    /// searching the buffered bytes for `seq` rather than reading
+    /// them one at a time, `None` when the stream is closed before
+    /// any byte and `UnexpectedEof` when it is cut off before `seq`
+    pub fn read_until_exclusive_at_most<const N: usize>(
+        &mut self,
+        seq: &[u8; N],
+        max: usize,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let mut data = Vec::new();
 
         loop {
-            let size = self.read_at_most(&mut buf)?;
-            data.put_slice(&buf);
+            let buf = self.inner.fill_buf()?;
+            let size = buf.len();
 
             if size == 0 {
-                break;
+                return match data.is_empty() {
+                    true => Ok(None),
+                    false => Err(truncated()),
+                };
             }
 
-            if data.ends_with(seq) {
-                data.truncate(data.len() - seq.len());
+            // `seq` may start in the bytes of the last fill
+            let from = data.len().saturating_sub(N - 1);
+            let before = data.len();
+            data.extend_from_slice(buf);
+
+            if let Some(i) = data[from..].windows(N).position(|v| v == seq) {
+                let end = from + i;
+                self.inner.consume(end + N - before);
+                data.truncate(end);
+
+                if end > max {
+                    return Err(too_long(max));
+                }
+
                 break;
             }
-        }
 
-        return Ok(data.to_vec());
-    }
+            self.inner.consume(size);
 
-    pub fn read_utf8_until_exclusive<const N: usize>(
-        &mut self,
-        seq: &[u8; N],
-    ) -> io::Result<String> {
-        let bytes = self.read_until_exclusive(seq)?;
-        return Ok(String::from_utf8_lossy(&bytes).to_string());
+            if data.len() > max.saturating_add(N - 1) {
+                return Err(too_long(max));
+            }
+        }
+
+        return Ok(Some(data));
     }
 }
 
@@ -132,3 +183,17 @@ impl<T: io::Read> io::Read for ByteReader<T> {
         return self.inner.read(buf);
     }
 }
+
+pub(crate) fn too_long(max: usize) -> io::Error {
+    return io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("[cube::core::bytes] => more than {} bytes", max),
+    );
+}
+
+pub(crate) fn truncated() -> io::Error {
+    return io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "[cube::core::bytes] => stream ended before the delimiter",
+    );
+}
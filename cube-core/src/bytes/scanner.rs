@@ -37,6 +37,10 @@ impl<'a> Scanner<'a> {
         return self.data.len();
     }
 
+    pub fn is_empty(&self) -> bool {
+        return self.data.is_empty();
+    }
+
     /// get the current byte
     pub fn curr(&self) -> u8 {
         return match self.data.as_bytes().get(self.right) {
@@ -48,23 +52,17 @@ impl<'a> Scanner<'a> {
     /// peek at the next byte
     /// without moving forward
     pub fn peek(&self) -> Option<u8> {
-        return match self.data.as_bytes().get(self.right + 1) {
-            None => None,
-            Some(v) => Some(*v),
-        };
+        return self.data.as_bytes().get(self.right + 1).copied();
     }
 
     /// peek at the Nth next byte
     /// without moving forward
     pub fn peek_offset(&self, n: isize) -> Option<u8> {
-        return match self
+        return self
             .data
             .as_bytes()
             .get(((self.right as isize) + n) as usize)
-        {
-            None => None,
-            Some(v) => Some(*v),
-        };
+            .copied();
     }
 
     /// peek at the next N bytes without
@@ -94,13 +92,13 @@ impl<'a> Scanner<'a> {
             return None;
         }
 
-        return match self.data.as_bytes().get(self.right) {
-            None => None,
-            Some(v) => Some(*v),
-        };
+        return self.data.as_bytes().get(self.right).copied();
     }
 
     /// move forward
+    // not `Iterator::next`, an `Iterator` impl would make calls on
+    // `&mut Scanner` such as `count` resolve to its methods instead
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<u8> {
         self.right += 1;
 
@@ -108,10 +106,7 @@ impl<'a> Scanner<'a> {
             return None;
         }
 
-        return match self.data.as_bytes().get(self.right) {
-            None => None,
-            Some(v) => Some(*v),
-        };
+        return self.data.as_bytes().get(self.right).copied();
     }
 
     /// move forward n times
@@ -124,10 +119,7 @@ impl<'a> Scanner<'a> {
             }
         }
 
-        return match self.data.as_bytes().get(self.right) {
-            None => None,
-            Some(v) => Some(*v),
-        };
+        return self.data.as_bytes().get(self.right).copied();
     }
 
     /// move forward if a given byte
@@ -214,13 +206,12 @@ impl<'a> Scanner<'a> {
         iter.goto(self.right);
 
         while !iter.is_eof() {
-            if iter.curr() == bytes[0] {
-                if let Some(v) = iter.peek_n(bytes.len() - 1)
-                    && v.as_bytes() == bytes
-                {
-                    self.right = iter.left - 1;
-                    break;
-                }
+            if iter.curr() == bytes[0]
+                && let Some(v) = iter.peek_n(bytes.len() - 1)
+                && v.as_bytes() == bytes
+            {
+                self.right = iter.left - 1;
+                break;
             }
 
             iter.fshift(1);
@@ -316,7 +307,7 @@ impl<'a> Scanner<'a> {
             end = self.data.len()
         }
 
-        return &self.data[self.left..end].as_bytes();
+        return self.data[self.left..end].as_bytes();
     }
 
     /// get the entire inner dataset
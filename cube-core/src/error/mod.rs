@@ -1,6 +1,6 @@
 use std::{error, fmt, io, num, string};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Error {
     pub message: Option<String>,
     pub errors: Vec<Error>,
@@ -8,10 +8,7 @@ pub struct Error {
 
 impl Error {
     pub fn new() -> Self {
-        return Self {
-            message: None,
-            errors: vec![],
-        };
+        return Self::default();
     }
 
     pub fn push(&mut self, error: &Error) -> &mut Self {
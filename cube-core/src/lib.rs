@@ -1,2 +1,5 @@
+// the code returns explicitly throughout, as a matter of style
+#![allow(clippy::needless_return)]
+
 pub mod bytes;
 pub mod error;
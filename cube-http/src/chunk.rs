@@ -0,0 +1,120 @@
+use std::{collections::HashMap, io};
+
+use cube_core::error::Error;
+
+/// Chunked Transfer Coding
+///
+/// https://www.rfc-editor.org/rfc/rfc9112#name-chunked-transfer-coding
+pub struct Chunk;
+
+impl Chunk {
+    /// encode data as a single chunk
+    pub fn encode(data: &[u8]) -> Vec<u8> {
+        let mut chunk = format!("{:x}\r\n", data.len()).into_bytes();
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(b"\r\n");
+        return chunk;
+    }
+
+    /// encode the last chunk followed by
+    /// the trailers that end the body
+    pub fn last(trailers: &HashMap<String, String>) -> Vec<u8> {
+        let mut chunk = String::from("0\r\n");
+
+        for (key, value) in trailers {
+            chunk.push_str(&format!("{}: {}\r\n", key, value));
+        }
+
+        chunk.push_str("\r\n");
+        return chunk.into_bytes();
+    }
+
+    /// parse the size from a chunk line,
+    /// ignoring any chunk extensions
+    pub fn parse_size(line: &str) -> Result<usize, Error> {
+        let size = line.split(';').next().unwrap_or("").trim();
+
+        if size.is_empty() || size.len() > 15 || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::from(format!(
+                "[cube::http::chunk] => invalid chunk size \"{}\"",
+                line
+            )));
+        }
+
+        return Ok(usize::from_str_radix(size, 16)?);
+    }
+
+    pub fn parse_trailer(line: &str) -> Result<(String, String), Error> {
+        return match line.split_once(':') {
+            None => Err(Error::from(format!(
+                "[cube::http::chunk] => invalid trailer \"{}\"",
+                line
+            ))),
+            Some((key, value)) => Ok((key.trim().to_string(), value.trim().to_string())),
+        };
+    }
+}
+
+/// writes everything as chunks until
+/// it is finished with the last chunk
+pub struct ChunkedWriter<W: io::Write> {
+    inner: W,
+}
+
+impl<W: io::Write> ChunkedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        return Self { inner };
+    }
+
+    pub fn finish(mut self, trailers: &HashMap<String, String>) -> io::Result<W> {
+        self.inner.write_all(&Chunk::last(trailers))?;
+        self.inner.flush()?;
+        return Ok(self.inner);
+    }
+}
+
+impl<W: io::Write> io::Write for ChunkedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.inner.write_all(&Chunk::encode(buf))?;
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, io::Write};
+
+    #[test]
+    pub fn should_encode() {
+        let mut writer = super::ChunkedWriter::new(Vec::<u8>::new());
+        writer.write_all(b"hello world!").unwrap();
+        writer.write_all(b"!").unwrap();
+
+        let mut trailers = HashMap::new();
+        trailers.insert(String::from("Expires"), String::from("0"));
+
+        let data = writer.finish(&trailers).unwrap();
+        assert_eq!(
+            data,
+            b"c\r\nhello world!\r\n1\r\n!\r\n0\r\nExpires: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    pub fn should_parse_size() {
+        assert_eq!(super::Chunk::parse_size("1a").unwrap(), 26);
+        assert_eq!(super::Chunk::parse_size("1A;name=value").unwrap(), 26);
+        assert_eq!(super::Chunk::parse_size("0").unwrap(), 0);
+        assert!(super::Chunk::parse_size("").is_err());
+        assert!(super::Chunk::parse_size("-1").is_err());
+        assert!(super::Chunk::parse_size("fffffffffffffffffff").is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use bytes::Bytes;
+use cube_core::error::Error;
+
+use crate::{ContentType, HttpError, Status};
+
+/// Body Codec
+///
+/// (de)serializes bodies of type `T` for the
+/// content types it supports
+pub trait Codec<T> {
+    /// the content type bodies are encoded as
+    fn content_type(&self) -> ContentType;
+
+    /// if a body sent as `content_type` can be decoded,
+    /// `None` when the request has no or an unknown type
+    fn supports(&self, content_type: Option<ContentType>) -> bool {
+        return content_type == Some(self.content_type());
+    }
+
+    fn decode(&self, body: &[u8]) -> Result<T, Error>;
+    fn encode(&self, value: &T) -> Result<Bytes, Error>;
+
+    /// decode a request body, rejecting an unsupported
+    /// content type with `415` and invalid bodies with `400`
+    fn decode_request(
+        &self,
+        content_type: Option<ContentType>,
+        body: &[u8],
+    ) -> Result<T, HttpError> {
+        if !self.supports(content_type) {
+            return Err(HttpError::new(Status::UnsupportedMediaType));
+        }
+
+        return self.decode(body).map_err(HttpError::from);
+    }
+}
+
+/// Codecs
+///
+/// the codecs a body of type `T` can be sent with,
+/// a request is decoded by the first one that
+/// supports its `Content-Type`
+///
+/// Example
+/// -------
+/// `Codecs::<User>::serde().decode_request(req.content_type(), &body)`
+pub struct Codecs<T> {
+    items: Vec<Box<dyn Codec<T>>>,
+}
+
+impl<T: 'static> Codecs<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self { items: vec![] };
+    }
+
+    pub fn push<C: Codec<T> + 'static>(&mut self, codec: C) -> &mut Self {
+        self.items.push(Box::new(codec));
+        return self;
+    }
+
+    /// the codec for a body sent as `content_type`
+    pub fn find(&self, content_type: Option<ContentType>) -> Option<&dyn Codec<T>> {
+        return self
+            .items
+            .iter()
+            .find(|codec| codec.supports(content_type))
+            .map(|codec| codec.as_ref());
+    }
+
+    /// decode a request body with the codec for its content
+    /// type, rejecting a type no codec supports with `415`
+    /// and invalid bodies with `400`
+    pub fn decode_request(
+        &self,
+        content_type: Option<ContentType>,
+        body: &[u8],
+    ) -> Result<T, HttpError> {
+        return match self.find(content_type) {
+            None => Err(HttpError::new(Status::UnsupportedMediaType)),
+            Some(codec) => codec.decode_request(content_type, body),
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned + 'static> Codecs<T> {
+    /// the `JsonCodec` and the `FormCodec`
+    pub fn serde() -> Self {
+        let mut codecs = Self::new();
+        codecs.push(JsonCodec).push(FormCodec);
+        return codecs;
+    }
+}
+
+/// raw bytes of any content type
+pub struct BytesCodec;
+
+impl Codec<Bytes> for BytesCodec {
+    fn content_type(&self) -> ContentType {
+        return ContentType::OctetStream;
+    }
+
+    fn supports(&self, _: Option<ContentType>) -> bool {
+        return true;
+    }
+
+    fn decode(&self, body: &[u8]) -> Result<Bytes, Error> {
+        return Ok(Bytes::copy_from_slice(body));
+    }
+
+    fn encode(&self, value: &Bytes) -> Result<Bytes, Error> {
+        return Ok(value.clone());
+    }
+}
+
+/// `UTF-8` text, of any text content type
+/// or when the request has none
+pub struct TextCodec;
+
+impl Codec<String> for TextCodec {
+    fn content_type(&self) -> ContentType {
+        return ContentType::PlainText;
+    }
+
+    fn supports(&self, content_type: Option<ContentType>) -> bool {
+        return content_type.is_none_or(|v| v.is_text());
+    }
+
+    fn decode(&self, body: &[u8]) -> Result<String, Error> {
+        return Ok(String::from_utf8(body.to_vec())?);
+    }
+
+    fn encode(&self, value: &String) -> Result<Bytes, Error> {
+        return Ok(Bytes::from(value.clone()));
+    }
+}
+
+/// https://www.rfc-editor.org/rfc/rfc8259
+#[cfg(feature = "serde")]
+pub struct JsonCodec;
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec<T> for JsonCodec {
+    fn content_type(&self) -> ContentType {
+        return ContentType::Json;
+    }
+
+    fn decode(&self, body: &[u8]) -> Result<T, Error> {
+        return serde_json::from_slice(body)
+            .map_err(|err| Error::from(format!("[cube::http::codec] => invalid json: {}", err)));
+    }
+
+    fn encode(&self, value: &T) -> Result<Bytes, Error> {
+        return match serde_json::to_vec(value) {
+            Ok(v) => Ok(Bytes::from(v)),
+            Err(err) => Err(Error::from(format!(
+                "[cube::http::codec] => invalid json: {}",
+                err
+            ))),
+        };
+    }
+}
+
+/// https://url.spec.whatwg.org/#application/x-www-form-urlencoded
+#[cfg(feature = "serde")]
+pub struct FormCodec;
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec<T> for FormCodec {
+    fn content_type(&self) -> ContentType {
+        return ContentType::FormUrlEncoded;
+    }
+
+    fn decode(&self, body: &[u8]) -> Result<T, Error> {
+        return serde_urlencoded::from_bytes(body)
+            .map_err(|err| Error::from(format!("[cube::http::codec] => invalid form: {}", err)));
+    }
+
+    fn encode(&self, value: &T) -> Result<Bytes, Error> {
+        return match serde_urlencoded::to_string(value) {
+            Ok(v) => Ok(Bytes::from(v)),
+            Err(err) => Err(Error::from(format!(
+                "[cube::http::codec] => invalid form: {}",
+                err
+            ))),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ContentType, Status};
+
+    use super::{Codec, TextCodec};
+
+    #[test]
+    pub fn should_decode_text() {
+        let body = TextCodec
+            .decode_request(Some(ContentType::PlainText), b"hello")
+            .unwrap();
+        assert_eq!(body, "hello");
+
+        let err = TextCodec
+            .decode_request(Some(ContentType::Png), b"hello")
+            .unwrap_err();
+        assert_eq!(err.status, Status::UnsupportedMediaType);
+
+        let err = TextCodec.decode_request(None, &[0xff, 0xfe]).unwrap_err();
+        assert_eq!(err.status, Status::BadRequest);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn should_decode_json_and_form() {
+        use std::collections::BTreeMap;
+
+        use super::{FormCodec, JsonCodec};
+
+        let body: BTreeMap<String, String> = JsonCodec
+            .decode_request(Some(ContentType::Json), br#"{"name":"test"}"#)
+            .unwrap();
+        assert_eq!(body.get("name").unwrap(), "test");
+
+        let err = Codec::<BTreeMap<String, String>>::decode_request(
+            &JsonCodec,
+            Some(ContentType::FormUrlEncoded),
+            b"name=test",
+        )
+        .unwrap_err();
+        assert_eq!(err.status, Status::UnsupportedMediaType);
+
+        let body: BTreeMap<String, String> = FormCodec
+            .decode_request(Some(ContentType::FormUrlEncoded), b"name=a+b&id=1")
+            .unwrap();
+        assert_eq!(body.get("name").unwrap(), "a b");
+        assert_eq!(
+            Codec::<BTreeMap<String, String>>::encode(&FormCodec, &body).unwrap(),
+            "id=1&name=a+b"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn should_pick_codec() {
+        use std::collections::BTreeMap;
+
+        use super::Codecs;
+
+        let codecs = Codecs::<BTreeMap<String, String>>::serde();
+        let body = codecs
+            .decode_request(Some(ContentType::Json), br#"{"name":"test"}"#)
+            .unwrap();
+        assert_eq!(body.get("name").unwrap(), "test");
+
+        let body = codecs
+            .decode_request(Some(ContentType::FormUrlEncoded), b"name=test")
+            .unwrap();
+        assert_eq!(body.get("name").unwrap(), "test");
+
+        let err = codecs
+            .decode_request(Some(ContentType::PlainText), b"name=test")
+            .unwrap_err();
+        assert_eq!(err.status, Status::UnsupportedMediaType);
+        assert!(codecs.find(None).is_none());
+    }
+}
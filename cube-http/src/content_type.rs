@@ -1,8 +1,10 @@
 use std::fmt;
 
+use cube_core::error::Error;
+
 /// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Content-Type
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ContentType {
     /// application/json
@@ -21,8 +23,8 @@ pub enum ContentType {
     #[cfg_attr(feature = "serde", serde(rename = "text/xml"))]
     Xml,
 
-    /// application/www-form-url-encoded
-    #[cfg_attr(feature = "serde", serde(rename = "application/www-form-url-encoded"))]
+    /// application/x-www-form-urlencoded
+    #[cfg_attr(feature = "serde", serde(rename = "application/x-www-form-urlencoded"))]
     FormUrlEncoded,
 
     /// image/jpeg
@@ -36,6 +38,235 @@ pub enum ContentType {
     /// application/octet-stream
     #[cfg_attr(feature = "serde", serde(rename = "application/octet-stream"))]
     OctetStream,
+
+    /// text/css; charset=utf-8
+    #[cfg_attr(feature = "serde", serde(rename = "text/css; charset=utf-8"))]
+    Css,
+
+    /// text/javascript; charset=utf-8
+    #[cfg_attr(feature = "serde", serde(rename = "text/javascript; charset=utf-8"))]
+    JavaScript,
+
+    /// text/csv; charset=utf-8
+    #[cfg_attr(feature = "serde", serde(rename = "text/csv; charset=utf-8"))]
+    Csv,
+
+    /// text/markdown; charset=utf-8
+    #[cfg_attr(feature = "serde", serde(rename = "text/markdown; charset=utf-8"))]
+    Markdown,
+
+    /// application/manifest+json
+    #[cfg_attr(feature = "serde", serde(rename = "application/manifest+json"))]
+    WebManifest,
+
+    /// image/svg+xml
+    #[cfg_attr(feature = "serde", serde(rename = "image/svg+xml"))]
+    Svg,
+
+    /// image/gif
+    #[cfg_attr(feature = "serde", serde(rename = "image/gif"))]
+    Gif,
+
+    /// image/webp
+    #[cfg_attr(feature = "serde", serde(rename = "image/webp"))]
+    Webp,
+
+    /// image/avif
+    #[cfg_attr(feature = "serde", serde(rename = "image/avif"))]
+    Avif,
+
+    /// image/x-icon
+    #[cfg_attr(feature = "serde", serde(rename = "image/x-icon"))]
+    Ico,
+
+    /// image/bmp
+    #[cfg_attr(feature = "serde", serde(rename = "image/bmp"))]
+    Bmp,
+
+    /// application/pdf
+    #[cfg_attr(feature = "serde", serde(rename = "application/pdf"))]
+    Pdf,
+
+    /// application/zip
+    #[cfg_attr(feature = "serde", serde(rename = "application/zip"))]
+    Zip,
+
+    /// application/gzip
+    #[cfg_attr(feature = "serde", serde(rename = "application/gzip"))]
+    Gzip,
+
+    /// application/wasm
+    #[cfg_attr(feature = "serde", serde(rename = "application/wasm"))]
+    Wasm,
+
+    /// font/woff
+    #[cfg_attr(feature = "serde", serde(rename = "font/woff"))]
+    Woff,
+
+    /// font/woff2
+    #[cfg_attr(feature = "serde", serde(rename = "font/woff2"))]
+    Woff2,
+
+    /// font/ttf
+    #[cfg_attr(feature = "serde", serde(rename = "font/ttf"))]
+    Ttf,
+
+    /// font/otf
+    #[cfg_attr(feature = "serde", serde(rename = "font/otf"))]
+    Otf,
+
+    /// audio/mpeg
+    #[cfg_attr(feature = "serde", serde(rename = "audio/mpeg"))]
+    Mp3,
+
+    /// audio/ogg
+    #[cfg_attr(feature = "serde", serde(rename = "audio/ogg"))]
+    Ogg,
+
+    /// audio/wav
+    #[cfg_attr(feature = "serde", serde(rename = "audio/wav"))]
+    Wav,
+
+    /// video/mp4
+    #[cfg_attr(feature = "serde", serde(rename = "video/mp4"))]
+    Mp4,
+
+    /// video/webm
+    #[cfg_attr(feature = "serde", serde(rename = "video/webm"))]
+    Webm,
+}
+
+impl ContentType {
+    /// if the content is text that
+    /// can be read as `UTF-8`
+    #[allow(clippy::match_like_matches_macro)]
+    pub fn is_text(&self) -> bool {
+        return match self {
+            Self::Json
+            | Self::PlainText
+            | Self::Html
+            | Self::Xml
+            | Self::FormUrlEncoded
+            | Self::Css
+            | Self::JavaScript
+            | Self::Csv
+            | Self::Markdown
+            | Self::WebManifest
+            | Self::Svg => true,
+            _ => false,
+        };
+    }
+
+    /// if the content is already compressed,
+    /// so compressing it again gains nothing
+    #[allow(clippy::match_like_matches_macro)]
+    pub fn is_compressed(&self) -> bool {
+        return match self {
+            Self::Jpg
+            | Self::Png
+            | Self::Gif
+            | Self::Webp
+            | Self::Avif
+            | Self::Pdf
+            | Self::Zip
+            | Self::Gzip
+            | Self::Woff
+            | Self::Woff2
+            | Self::Mp3
+            | Self::Ogg
+            | Self::Mp4
+            | Self::Webm => true,
+            _ => false,
+        };
+    }
+
+    /// the content type of a file extension,
+    /// ignoring its case
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        return match ext.to_lowercase().as_str() {
+            "json" | "map" => Some(Self::Json),
+            "txt" | "text" | "log" => Some(Self::PlainText),
+            "html" | "htm" => Some(Self::Html),
+            "xml" => Some(Self::Xml),
+            "jpg" | "jpeg" => Some(Self::Jpg),
+            "png" => Some(Self::Png),
+            "bin" => Some(Self::OctetStream),
+            "css" => Some(Self::Css),
+            "js" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "csv" => Some(Self::Csv),
+            "md" | "markdown" => Some(Self::Markdown),
+            "webmanifest" => Some(Self::WebManifest),
+            "svg" => Some(Self::Svg),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::Webp),
+            "avif" => Some(Self::Avif),
+            "ico" => Some(Self::Ico),
+            "bmp" => Some(Self::Bmp),
+            "pdf" => Some(Self::Pdf),
+            "zip" => Some(Self::Zip),
+            "gz" | "tgz" => Some(Self::Gzip),
+            "wasm" => Some(Self::Wasm),
+            "woff" => Some(Self::Woff),
+            "woff2" => Some(Self::Woff2),
+            "ttf" => Some(Self::Ttf),
+            "otf" => Some(Self::Otf),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "oga" => Some(Self::Ogg),
+            "wav" => Some(Self::Wav),
+            "mp4" => Some(Self::Mp4),
+            "webm" => Some(Self::Webm),
+            _ => None,
+        };
+    }
+}
+
+/// parse the media type of a `Content-Type` header,
+/// ignoring its case and any parameters
+impl TryFrom<&str> for ContentType {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mime = value.split(';').next().unwrap_or("").trim().to_lowercase();
+
+        return match mime.as_str() {
+            "application/json" => Ok(Self::Json),
+            "text/plain" => Ok(Self::PlainText),
+            "text/html" => Ok(Self::Html),
+            "text/xml" | "application/xml" => Ok(Self::Xml),
+            "application/x-www-form-urlencoded" => Ok(Self::FormUrlEncoded),
+            "image/jpeg" => Ok(Self::Jpg),
+            "image/png" => Ok(Self::Png),
+            "application/octet-stream" => Ok(Self::OctetStream),
+            "text/css" => Ok(Self::Css),
+            "text/javascript" | "application/javascript" => Ok(Self::JavaScript),
+            "text/csv" => Ok(Self::Csv),
+            "text/markdown" => Ok(Self::Markdown),
+            "application/manifest+json" => Ok(Self::WebManifest),
+            "image/svg+xml" => Ok(Self::Svg),
+            "image/gif" => Ok(Self::Gif),
+            "image/webp" => Ok(Self::Webp),
+            "image/avif" => Ok(Self::Avif),
+            "image/x-icon" | "image/vnd.microsoft.icon" => Ok(Self::Ico),
+            "image/bmp" => Ok(Self::Bmp),
+            "application/pdf" => Ok(Self::Pdf),
+            "application/zip" => Ok(Self::Zip),
+            "application/gzip" => Ok(Self::Gzip),
+            "application/wasm" => Ok(Self::Wasm),
+            "font/woff" => Ok(Self::Woff),
+            "font/woff2" => Ok(Self::Woff2),
+            "font/ttf" => Ok(Self::Ttf),
+            "font/otf" => Ok(Self::Otf),
+            "audio/mpeg" => Ok(Self::Mp3),
+            "audio/ogg" => Ok(Self::Ogg),
+            "audio/wav" => Ok(Self::Wav),
+            "video/mp4" => Ok(Self::Mp4),
+            "video/webm" => Ok(Self::Webm),
+            _ => Err(Error::from(format!(
+                "[cube::http::content_type] => unsupported content type \"{}\"",
+                value
+            ))),
+        };
+    }
 }
 
 impl fmt::Display for ContentType {
@@ -45,10 +276,71 @@ impl fmt::Display for ContentType {
             Self::PlainText => write!(f, "text/plain; charset=utf-8"),
             Self::Html => write!(f, "text/html; charset=utf-8"),
             Self::Xml => write!(f, "text/xml"),
-            Self::FormUrlEncoded => write!(f, "application/www-form-url-encoded"),
+            Self::FormUrlEncoded => write!(f, "application/x-www-form-urlencoded"),
             Self::Jpg => write!(f, "image/jpeg"),
             Self::Png => write!(f, "image/png"),
             Self::OctetStream => write!(f, "application/octet-stream"),
+            Self::Css => write!(f, "text/css; charset=utf-8"),
+            Self::JavaScript => write!(f, "text/javascript; charset=utf-8"),
+            Self::Csv => write!(f, "text/csv; charset=utf-8"),
+            Self::Markdown => write!(f, "text/markdown; charset=utf-8"),
+            Self::WebManifest => write!(f, "application/manifest+json"),
+            Self::Svg => write!(f, "image/svg+xml"),
+            Self::Gif => write!(f, "image/gif"),
+            Self::Webp => write!(f, "image/webp"),
+            Self::Avif => write!(f, "image/avif"),
+            Self::Ico => write!(f, "image/x-icon"),
+            Self::Bmp => write!(f, "image/bmp"),
+            Self::Pdf => write!(f, "application/pdf"),
+            Self::Zip => write!(f, "application/zip"),
+            Self::Gzip => write!(f, "application/gzip"),
+            Self::Wasm => write!(f, "application/wasm"),
+            Self::Woff => write!(f, "font/woff"),
+            Self::Woff2 => write!(f, "font/woff2"),
+            Self::Ttf => write!(f, "font/ttf"),
+            Self::Otf => write!(f, "font/otf"),
+            Self::Mp3 => write!(f, "audio/mpeg"),
+            Self::Ogg => write!(f, "audio/ogg"),
+            Self::Wav => write!(f, "audio/wav"),
+            Self::Mp4 => write!(f, "video/mp4"),
+            Self::Webm => write!(f, "video/webm"),
         };
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ContentType;
+
+    #[test]
+    pub fn should_parse() {
+        assert_eq!(
+            ContentType::try_from("application/json").unwrap(),
+            ContentType::Json
+        );
+        assert_eq!(
+            ContentType::try_from("Text/Plain; charset=UTF-8").unwrap(),
+            ContentType::PlainText
+        );
+        assert_eq!(
+            ContentType::try_from("application/x-www-form-urlencoded").unwrap(),
+            ContentType::FormUrlEncoded
+        );
+        assert_eq!(ContentType::try_from("text/csv").unwrap(), ContentType::Csv);
+        assert!(ContentType::try_from("application/x-unknown").is_err());
+    }
+
+    #[test]
+    pub fn should_map_extensions() {
+        assert_eq!(ContentType::from_extension("CSS"), Some(ContentType::Css));
+        assert_eq!(
+            ContentType::from_extension("mjs"),
+            Some(ContentType::JavaScript)
+        );
+        assert_eq!(
+            ContentType::from_extension("woff2"),
+            Some(ContentType::Woff2)
+        );
+        assert_eq!(ContentType::from_extension("unknown"), None);
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+use cube_core::error::Error;
+
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Set-Cookie#samesitesamesite-value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl TryFrom<&str> for SameSite {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        return match value.trim().to_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "lax" => Ok(Self::Lax),
+            "none" => Ok(Self::None),
+            _ => Err(Error::from(format!(
+                "[cube::http::cookie] => invalid same site \"{}\"",
+                value
+            ))),
+        };
+    }
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Strict => write!(f, "Strict"),
+            Self::Lax => write!(f, "Lax"),
+            Self::None => write!(f, "None"),
+        };
+    }
+}
+
+/// Cookie
+///
+/// a name and value sent by the client in the `Cookie`
+/// header, or by the server in `Set-Cookie` along with
+/// the attributes that scope it
+///
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Set-Cookie
+///
+/// Example
+/// -------
+/// `Cookie::new("id", "abc").path("/").http_only(true).same_site(SameSite::Lax)`
+/// =>
+/// `id=abc; Path=/; HttpOnly; SameSite=Lax`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub expires: Option<SystemTime>,
+    pub max_age: Option<Duration>,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+    pub partitioned: bool,
+}
+
+impl Cookie {
+    pub fn new(name: &str, value: &str) -> Self {
+        return Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            partitioned: false,
+        };
+    }
+
+    /// a cookie that tells the client
+    /// to remove the cookie `name`
+    pub fn removal(name: &str) -> Self {
+        let mut cookie = Self::new(name, "");
+        cookie
+            .expires(SystemTime::UNIX_EPOCH)
+            .max_age(Duration::ZERO);
+        return cookie;
+    }
+
+    /// parse the cookies of a request `Cookie` header,
+    /// skipping pairs without a name
+    pub fn parse(header: &str) -> Vec<Self> {
+        return header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                let name = name.trim();
+
+                if name.is_empty() {
+                    return None;
+                }
+
+                return Some(Self::new(name, unquote(value.trim())));
+            })
+            .collect();
+    }
+
+    pub fn expires(&mut self, expires: SystemTime) -> &mut Self {
+        self.expires = Some(expires);
+        return self;
+    }
+
+    pub fn max_age(&mut self, max_age: Duration) -> &mut Self {
+        self.max_age = Some(max_age);
+        return self;
+    }
+
+    pub fn domain(&mut self, domain: &str) -> &mut Self {
+        self.domain = Some(domain.to_string());
+        return self;
+    }
+
+    pub fn path(&mut self, path: &str) -> &mut Self {
+        self.path = Some(path.to_string());
+        return self;
+    }
+
+    /// only send the cookie over `https`
+    pub fn secure(&mut self, secure: bool) -> &mut Self {
+        self.secure = secure;
+        return self;
+    }
+
+    /// hide the cookie from scripts
+    pub fn http_only(&mut self, http_only: bool) -> &mut Self {
+        self.http_only = http_only;
+        return self;
+    }
+
+    /// `SameSite::None` requires `secure`
+    pub fn same_site(&mut self, same_site: SameSite) -> &mut Self {
+        self.same_site = Some(same_site);
+        return self;
+    }
+
+    /// store the cookie separately for each top level
+    /// site it is embedded in, requires `secure`
+    pub fn partitioned(&mut self, partitioned: bool) -> &mut Self {
+        self.partitioned = partitioned;
+        return self;
+    }
+
+    /// if the cookie tells the client to remove it
+    pub fn is_removal(&self) -> bool {
+        return self.max_age == Some(Duration::ZERO)
+            || self.expires.is_some_and(|v| v <= SystemTime::UNIX_EPOCH);
+    }
+
+    /// check that the cookie can be written to a `Set-Cookie`
+    /// header as is, so a value taken from a request can't add
+    /// attributes or break the header
+    /// https://www.rfc-editor.org/rfc/rfc6265#section-4.1.1
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty() || !self.name.bytes().all(is_tchar) {
+            return Err(Error::from(format!(
+                "[cube::http::cookie] => invalid name \"{}\"",
+                self.name
+            )));
+        }
+
+        let value = match self.value.len() > 1 {
+            true => unquote(&self.value),
+            false => &self.value,
+        };
+
+        if !value.bytes().all(is_cookie_octet) {
+            return Err(Error::from(format!(
+                "[cube::http::cookie] => invalid value for \"{}\"",
+                self.name
+            )));
+        }
+
+        for attribute in [&self.domain, &self.path].into_iter().flatten() {
+            if attribute.bytes().any(|c| c.is_ascii_control() || c == b';') {
+                return Err(Error::from(format!(
+                    "[cube::http::cookie] => invalid attribute \"{}\"",
+                    attribute
+                )));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+/// parse a `Set-Cookie` header, ignoring unknown attributes
+impl TryFrom<&str> for Cookie {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parts = value.split(';');
+        let pair = parts.next().unwrap_or("");
+        let Some((name, value)) = pair.split_once('=') else {
+            return Err(Error::from(format!(
+                "[cube::http::cookie] => invalid cookie \"{}\"",
+                pair
+            )));
+        };
+
+        let mut cookie = Self::new(name.trim(), unquote(value.trim()));
+
+        for part in parts {
+            let (key, value) = match part.split_once('=') {
+                None => (part.trim(), ""),
+                Some((k, v)) => (k.trim(), v.trim()),
+            };
+
+            match key.to_lowercase().as_str() {
+                "expires" => {
+                    if let Ok(v) = httpdate::parse_http_date(value) {
+                        cookie.expires(v);
+                    }
+                }
+                "max-age" => {
+                    if let Ok(v) = value.parse::<i64>() {
+                        cookie.max_age(Duration::from_secs(v.max(0) as u64));
+                    }
+                }
+                "domain" => {
+                    cookie.domain(value.trim_start_matches('.'));
+                }
+                "path" => {
+                    cookie.path(value);
+                }
+                "secure" => {
+                    cookie.secure(true);
+                }
+                "httponly" => {
+                    cookie.http_only(true);
+                }
+                "samesite" => {
+                    cookie.same_site(SameSite::try_from(value)?);
+                }
+                "partitioned" => {
+                    cookie.partitioned(true);
+                }
+                _ => {}
+            };
+        }
+
+        return Ok(cookie);
+    }
+}
+
+/// the `Set-Cookie` header value
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", httpdate::fmt_http_date(expires))?;
+        }
+
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+
+        if self.partitioned {
+            write!(f, "; Partitioned")?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// https://www.rfc-editor.org/rfc/rfc9110#section-5.6.2
+pub(crate) fn is_tchar(c: u8) -> bool {
+    return c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c);
+}
+
+/// any visible ascii but `"`, `,`, `;` and `\\`
+fn is_cookie_octet(c: u8) -> bool {
+    return matches!(c, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E);
+}
+
+fn unquote(value: &str) -> &str {
+    return value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use super::{Cookie, SameSite};
+
+    #[test]
+    pub fn should_parse() {
+        let cookies = Cookie::parse("id=abc; theme=\"dark\"; =skip; empty=");
+
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies[0].name, "id");
+        assert_eq!(cookies[0].value, "abc");
+        assert_eq!(cookies[1].value, "dark");
+        assert_eq!(cookies[2].value, "");
+    }
+
+    #[test]
+    pub fn should_format() {
+        let mut cookie = Cookie::new("id", "abc");
+        cookie
+            .expires(SystemTime::UNIX_EPOCH + Duration::from_secs(784111777))
+            .max_age(Duration::from_secs(3600))
+            .domain("example.com")
+            .path("/")
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::None)
+            .partitioned(true);
+
+        let value = cookie.to_string();
+        assert_eq!(
+            value,
+            "id=abc; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Max-Age=3600; Domain=example.com; Path=/; Secure; HttpOnly; SameSite=None; Partitioned"
+        );
+        assert_eq!(Cookie::try_from(value.as_str()).unwrap(), cookie);
+        assert!(Cookie::removal("id").is_removal());
+    }
+
+    #[test]
+    pub fn should_validate() {
+        assert!(Cookie::new("id", "abc").validate().is_ok());
+        assert!(Cookie::new("id", "\"a=b\"").validate().is_ok());
+        assert!(Cookie::removal("id").validate().is_ok());
+        assert!(Cookie::new("", "abc").validate().is_err());
+        assert!(Cookie::new("a b", "abc").validate().is_err());
+        assert!(
+            Cookie::new("id", "abc; Domain=evil.com")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            Cookie::new("id", "abc\r\nX-Injected: 1")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            Cookie::new("id", "abc")
+                .path("/; Secure")
+                .validate()
+                .is_err()
+        );
+    }
+}
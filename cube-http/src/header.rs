@@ -5,6 +5,21 @@ use std::fmt;
 pub enum Header {
     #[cfg_attr(feature = "serde", serde(untagged))]
     Raw(String),
+
+    /// a field sent on its own line for each
+    /// value, such as `Set-Cookie`
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    List(Vec<String>),
+}
+
+impl Header {
+    /// the values of the field, one for each line it is sent on
+    pub fn values(&self) -> Vec<&str> {
+        return match self {
+            Self::Raw(v) => vec![v.as_str()],
+            Self::List(v) => v.iter().map(String::as_str).collect(),
+        };
+    }
 }
 
 impl From<&str> for Header {
@@ -23,6 +38,7 @@ impl fmt::Display for Header {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match self {
             Self::Raw(v) => write!(f, "{}", v),
+            Self::List(v) => write!(f, "{}", v.join(", ")),
         };
     }
 }
@@ -13,34 +13,71 @@ pub struct Headers {
 }
 
 impl Headers {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         return Self {
             data: BTreeMap::new(),
         };
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         return self.data.len();
     }
 
     pub fn has(&self, name: &str) -> bool {
-        return self.data.contains_key(name);
+        return self.get(name).is_some();
     }
 
+    /// the stored name of a header, which
+    /// can differ from `name` in case
+    fn key(&self, name: &str) -> Option<String> {
+        if self.data.contains_key(name) {
+            return Some(name.to_string());
+        }
+
+        return self
+            .data
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .cloned();
+    }
+
+    /// get a header, ignoring the case of its name
     pub fn get(&self, name: &str) -> Option<&Header> {
-        return self.data.get(name);
+        return self.data.get(&self.key(name)?);
     }
 
     pub fn get_mut(&mut self, name: &str) -> Option<&mut Header> {
-        return self.data.get_mut(name);
+        let key = self.key(name)?;
+        return self.data.get_mut(&key);
     }
 
+    /// set a header, replacing it under any case of its name
     pub fn set(&mut self, name: &str, value: &Header) {
+        self.del(name);
         self.data.insert(name.to_string(), value.clone());
     }
 
+    /// add a value to a header, sending it on its own
+    /// line when the header already has a value
+    pub fn add(&mut self, name: &str, value: &str) {
+        let key = self.key(name).unwrap_or(name.to_string());
+        let header = match self.data.remove(&key) {
+            None => Header::Raw(value.to_string()),
+            Some(Header::Raw(v)) => Header::List(vec![v, value.to_string()]),
+            Some(Header::List(mut v)) => {
+                v.push(value.to_string());
+                Header::List(v)
+            }
+        };
+
+        self.data.insert(key, header);
+    }
+
+    /// remove a header under any case of its name
     pub fn del(&mut self, name: &str) {
-        self.data.remove(name);
+        self.data.retain(|key, _| !key.eq_ignore_ascii_case(name));
     }
 }
 
@@ -65,6 +102,7 @@ impl From<&HashMap<String, String>> for Headers {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<HashMap<String, String>> for Headers {
     fn into(self) -> HashMap<String, String> {
         let mut headers = HashMap::<String, String>::new();
@@ -77,6 +115,23 @@ impl Into<HashMap<String, String>> for Headers {
     }
 }
 
+/// one name and value for each line the headers are sent on
+#[allow(clippy::from_over_into)]
+impl Into<Vec<(String, String)>> for Headers {
+    fn into(self) -> Vec<(String, String)> {
+        let mut lines = Vec::<(String, String)>::new();
+
+        for (key, value) in self.data {
+            match value {
+                Header::Raw(v) => lines.push((key, v)),
+                Header::List(v) => lines.extend(v.into_iter().map(|v| (key.clone(), v))),
+            };
+        }
+
+        return lines;
+    }
+}
+
 #[cfg(feature = "serde")]
 impl std::fmt::Display for Headers {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -85,3 +140,27 @@ impl std::fmt::Display for Headers {
         return write!(f, "{}", json);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::Header;
+
+    #[test]
+    pub fn should_ignore_case() {
+        let mut headers = super::Headers::new();
+        headers.set("content-length", &Header::from("5"));
+        headers.set("Content-Length", &Header::from("7"));
+
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers.get("CONTENT-LENGTH").unwrap().to_string(), "7");
+
+        *headers.get_mut("content-length").unwrap() = Header::from("9");
+        assert_eq!(headers.get("Content-Length").unwrap().to_string(), "9");
+
+        headers.add("content-length", "10");
+        assert_eq!(headers.len(), 1);
+
+        headers.del("CONTENT-length");
+        assert!(!headers.has("Content-Length"));
+    }
+}
@@ -0,0 +1,59 @@
+use std::{error, fmt, io};
+
+use cube_core::error::Error;
+
+use crate::Status;
+
+/// an error that maps to the status
+/// the server should respond with
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    pub status: Status,
+    pub message: Option<String>,
+}
+
+impl HttpError {
+    pub fn new(status: Status) -> Self {
+        return Self {
+            status,
+            message: None,
+        };
+    }
+
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        return self;
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status.as_u16(), self.status)?;
+
+        if let Some(v) = &self.message {
+            write!(f, ": {}", v)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl error::Error for HttpError {}
+
+impl From<Status> for HttpError {
+    fn from(value: Status) -> Self {
+        return Self::new(value);
+    }
+}
+
+impl From<Error> for HttpError {
+    fn from(value: Error) -> Self {
+        return Self::new(Status::BadRequest).message(&value.to_string());
+    }
+}
+
+impl From<io::Error> for HttpError {
+    fn from(value: io::Error) -> Self {
+        return Self::new(Status::BadRequest).message(&value.to_string());
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 mod method;
 pub use method::*;
 
@@ -13,11 +15,26 @@ pub use headers::*;
 mod header;
 pub use header::*;
 
+mod cookie;
+pub use cookie::*;
+
+mod limits;
+pub use limits::*;
+
 mod request_message;
 pub use request_message::*;
 
 mod response_message;
 pub use response_message::*;
 
+mod http_error;
+pub use http_error::*;
+
+mod chunk;
+pub use chunk::*;
+
+mod codec;
+pub use codec::*;
+
 #[cfg(feature = "server")]
 pub mod server;
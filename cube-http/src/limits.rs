@@ -0,0 +1,39 @@
+/// Request Limits
+///
+/// the max sizes of a request head, reading
+/// past them fails with the status the
+/// request should be answered with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// the max size in bytes of the request
+    /// line, answered with `414`
+    pub max_line_size: Option<usize>,
+
+    /// the max size in bytes of all header
+    /// lines, answered with `431`
+    pub max_header_size: Option<usize>,
+
+    /// the max number of header lines,
+    /// answered with `431`
+    pub max_headers: Option<usize>,
+}
+
+impl Limits {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            max_line_size: Some(8 * 1024),
+            max_header_size: Some(32 * 1024),
+            max_headers: Some(100),
+        };
+    }
+
+    /// no limits at all
+    pub fn none() -> Self {
+        return Self {
+            max_line_size: None,
+            max_header_size: None,
+            max_headers: None,
+        };
+    }
+}
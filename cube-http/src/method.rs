@@ -88,6 +88,7 @@ impl TryFrom<String> for Method {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<String> for Method {
     fn into(self) -> String {
         return self.to_string();
@@ -1,10 +1,11 @@
 use std::io::Write;
-use std::{collections::HashMap, net};
+use std::{collections::HashMap, io, net};
 
+use bytes::{BufMut, Bytes, BytesMut};
 use cube_core::bytes::ByteReader;
 use cube_core::error::Error;
 
-use crate::Method;
+use crate::{Chunk, HttpError, Limits, Method, Status, cookie::is_tchar};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -14,39 +15,333 @@ pub struct RequestMessage {
     pub protocol: String,
     pub protocol_v: String,
     pub headers: HashMap<String, String>,
+    pub body: Bytes,
+    pub trailers: HashMap<String, String>,
 }
 
 impl RequestMessage {
-    pub fn read(stream: &net::TcpStream) -> Result<Self, Error> {
-        let mut reader = ByteReader::new(stream);
-        let mut message = Self {
-            method: Method::try_from(reader.read_utf8_until_exclusive(b" ")?)?,
-            path: reader.read_utf8_until_exclusive(b" ")?,
-            protocol: reader.read_utf8_until_exclusive(b"/")?.to_lowercase(),
-            protocol_v: reader.read_utf8_until_exclusive(b"\r\n")?,
+    /// get a header value, ignoring the case of its name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        return match self.headers.get(name) {
+            Some(v) => Some(v),
+            None => self
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str()),
+        };
+    }
+
+    /// if the connection should stay open after the response,
+    /// `HTTP/1.1` defaults to persistent and `HTTP/1.0` does not
+    /// https://www.rfc-editor.org/rfc/rfc9112#name-persistence
+    pub fn is_keep_alive(&self) -> bool {
+        let connection = self.header("Connection").unwrap_or("").to_lowercase();
+        let mut options = connection.split(',').map(|v| v.trim());
+
+        if options.clone().any(|v| v == "close") {
+            return false;
+        }
+
+        if self.protocol_v == "1.0" {
+            return options.any(|v| v == "keep-alive");
+        }
+
+        return true;
+    }
+
+    /// the length of the body, `None` when the header is
+    /// missing and an error when it is not a valid length
+    /// https://www.rfc-editor.org/rfc/rfc9110#name-content-length
+    pub fn content_length(&self) -> Result<Option<usize>, Error> {
+        let Some(value) = self.header("Content-Length") else {
+            return Ok(None);
+        };
+
+        let mut length: Option<usize> = None;
+
+        for v in value.split(',').map(|v| v.trim()) {
+            if v.is_empty() || !v.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::from(format!(
+                    "[cube::http::request] => invalid content length \"{}\"",
+                    value
+                )));
+            }
+
+            let parsed: usize = v.parse()?;
+
+            if length.is_some_and(|l| l != parsed) {
+                return Err(Error::from(format!(
+                    "[cube::http::request] => conflicting content lengths \"{}\"",
+                    value
+                )));
+            }
+
+            length = Some(parsed);
+        }
+
+        return Ok(length);
+    }
+
+    /// if the body is sent with the chunked transfer coding,
+    /// which takes precedence over any `Content-Length`, an
+    /// error when another coding is last since the length of
+    /// the body can't be determined
+    /// https://www.rfc-editor.org/rfc/rfc9112#name-message-body-length
+    pub fn is_chunked(&self) -> Result<bool, Error> {
+        let Some(value) = self.header("Transfer-Encoding") else {
+            return Ok(false);
+        };
+
+        let last = value.rsplit(',').next().unwrap_or("").trim();
+
+        if !last.eq_ignore_ascii_case("chunked") {
+            return Err(Error::from(format!(
+                "[cube::http::request] => unsupported transfer encoding \"{}\"",
+                value
+            )));
+        }
+
+        return Ok(true);
+    }
+
+    /// split a header line into its name and value,
+    /// dropping the quotes around the value, a name must
+    /// be a token right up to the colon so it can't be
+    /// read as another field by a proxy that trims it
+    /// https://www.rfc-editor.org/rfc/rfc9112#section-5.1
+    fn parse_header(line: &str) -> Result<(String, String), Error> {
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(Error::from(format!(
+                "[cube::http::request] => invalid header \"{}\"",
+                line
+            )));
+        };
+
+        if key.is_empty() || !key.bytes().all(is_tchar) {
+            return Err(Error::from(format!(
+                "[cube::http::request] => invalid header name \"{}\"",
+                key.escape_debug()
+            )));
+        }
+
+        if value.contains(['\r', '\n', '\0']) {
+            return Err(Error::from(format!(
+                "[cube::http::request] => invalid value for header \"{}\"",
+                key
+            )));
+        }
+
+        let mut value = value.trim();
+
+        if let Some(v) = value.strip_prefix("\"") {
+            value = v;
+        }
+
+        if let Some(v) = value.strip_suffix("\"") {
+            value = v;
+        }
+
+        return Ok((key.to_string(), value.to_string()));
+    }
+
+    /// add a header line, joining the values of repeated
+    /// fields so a second `Host` or `Content-Length` can't
+    /// silently replace the first
+    /// https://www.rfc-editor.org/rfc/rfc9110#section-5.3
+    fn push_header(&mut self, line: &str) -> Result<(), Error> {
+        let (key, value) = Self::parse_header(line)?;
+        let existing = self
+            .headers
+            .keys()
+            .find(|k| k.eq_ignore_ascii_case(&key))
+            .cloned();
+
+        match existing {
+            None => self.headers.insert(key, value),
+            Some(k) => {
+                // cookies are separated by `;` instead of `,`
+                let separator = match k.eq_ignore_ascii_case("Cookie") {
+                    true => "; ",
+                    false => ", ",
+                };
+
+                let joined = format!("{}{}{}", self.headers[&k], separator, value);
+                self.headers.insert(k, joined)
+            }
+        };
+
+        return Ok(());
+    }
+
+    /// parse the request line, leaving the headers empty
+    fn parse_line(line: &str) -> Result<Self, Error> {
+        let parts = line.split(' ').collect::<Vec<_>>();
+        let [method, path, version] = parts.as_slice() else {
+            return Err(Error::from(format!(
+                "[cube::http::request] => invalid request line \"{}\"",
+                line
+            )));
+        };
+
+        let Some((protocol, protocol_v)) = version.split_once('/') else {
+            return Err(Error::from(format!(
+                "[cube::http::request] => invalid protocol \"{}\"",
+                version
+            )));
+        };
+
+        return Ok(Self {
+            method: Method::try_from(*method)?,
+            path: path.to_string(),
+            protocol: protocol.to_lowercase(),
+            protocol_v: protocol_v.to_string(),
             headers: HashMap::new(),
+            body: Bytes::new(),
+            trailers: HashMap::new(),
+        });
+    }
+
+    /// map a line that grew past its limit to `status`
+    fn limit_error(err: io::Error, status: Status) -> HttpError {
+        return match err.kind() {
+            io::ErrorKind::InvalidData => HttpError::new(status),
+            _ => HttpError::from(err),
         };
+    }
+
+    /// the connection closed before the request line
+    fn closed() -> HttpError {
+        return HttpError::new(Status::BadRequest).message("connection closed");
+    }
+
+    /// the stream ended in the middle of a message
+    fn truncated() -> HttpError {
+        return HttpError::new(Status::BadRequest).message("truncated message");
+    }
+
+    /// read the request line and headers, failing with
+    /// `414` or `431` once they grow past the limits
+    pub fn read<R: io::BufRead>(stream: R, limits: &Limits) -> Result<Self, HttpError> {
+        let mut reader = ByteReader::new(stream);
+        let line = reader
+            .read_until_exclusive_at_most(b"\r\n", limits.max_line_size.unwrap_or(usize::MAX))
+            .map_err(|err| Self::limit_error(err, Status::URITooLong))?
+            .ok_or_else(Self::closed)?;
+
+        let mut message = Self::parse_line(&String::from_utf8_lossy(&line))?;
+        let mut size = 0;
+        let mut count = 0;
+
+        loop {
+            let max = limits
+                .max_header_size
+                .map_or(usize::MAX, |max| max.saturating_sub(size));
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", max)
+                .map_err(|err| Self::limit_error(err, Status::RequestHeaderFieldsTooLarge))?
+                .ok_or_else(Self::truncated)?;
+
+            if line.is_empty() {
+                break;
+            }
+
+            size += line.len() + 2;
+            count += 1;
+
+            if limits.max_headers.is_some_and(|max| count > max) {
+                return Err(HttpError::new(Status::RequestHeaderFieldsTooLarge));
+            }
+
+            message.push_header(&String::from_utf8_lossy(&line))?;
+        }
+
+        return Ok(message);
+    }
+
+    /// read exactly `length` bytes of body
+    /// following the header block
+    pub fn read_body<R: io::Read>(&mut self, stream: R, length: usize) -> Result<(), Error> {
+        let mut reader = ByteReader::new(stream);
+        let buf = reader.read_to_vec_at_most(length)?;
+
+        if buf.len() < length {
+            return Err(Error::from(format!(
+                "[cube::http::request] => expected {} bytes of body, found {}",
+                length,
+                buf.len()
+            )));
+        }
+
+        self.body = Bytes::from(buf);
+        return Ok(());
+    }
+
+    /// read a chunked body and its trailers, ignoring chunk
+    /// extensions and failing once the body grows past `max`
+    /// bytes or its size lines and trailers grow past `limits`
+    pub fn read_chunked<R: io::BufRead>(
+        &mut self,
+        stream: R,
+        max: Option<usize>,
+        limits: &Limits,
+    ) -> Result<(), HttpError> {
+        let mut reader = ByteReader::new(stream);
+        let mut body = BytesMut::new();
 
         loop {
-            let key = reader.read_utf8_until_exclusive(b": ")?;
-            let mut value = reader.read_utf8_until_exclusive(b"\r\n")?;
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", limits.max_line_size.unwrap_or(usize::MAX))
+                .map_err(|err| Self::limit_error(err, Status::BadRequest))?
+                .ok_or_else(Self::truncated)?;
+            let size = Chunk::parse_size(&String::from_utf8_lossy(&line))?;
+
+            if size == 0 {
+                break;
+            }
 
-            if value.starts_with("\"") {
-                value = value.strip_prefix("\"").unwrap().to_string();
+            if max.is_some_and(|max| body.len() + size > max) {
+                return Err(HttpError::new(Status::ContentTooLarge));
             }
 
-            if value.ends_with("\"") {
-                value = value.strip_suffix("\"").unwrap().to_string();
+            let buf = reader.read_to_vec_at_most(size)?;
+
+            if buf.len() < size || reader.read::<2>()? != *b"\r\n" {
+                return Err(HttpError::new(Status::BadRequest).message("incomplete chunk"));
             }
 
-            message.headers.insert(key, value);
+            body.put_slice(&buf);
+        }
+
+        let mut size = 0;
+        let mut count = 0;
+
+        loop {
+            let max = limits
+                .max_header_size
+                .map_or(usize::MAX, |max| max.saturating_sub(size));
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", max)
+                .map_err(|err| Self::limit_error(err, Status::RequestHeaderFieldsTooLarge))?
+                .ok_or_else(Self::truncated)?;
 
-            if reader.next_if(b"\r\n") {
+            if line.is_empty() {
                 break;
             }
+
+            size += line.len() + 2;
+            count += 1;
+
+            if limits.max_headers.is_some_and(|max| count > max) {
+                return Err(HttpError::new(Status::RequestHeaderFieldsTooLarge));
+            }
+
+            let (key, value) = Chunk::parse_trailer(&String::from_utf8_lossy(&line))?;
+            self.trailers.insert(key, value);
         }
 
-        return Ok(message);
+        self.body = body.freeze();
+        return Ok(());
     }
 
     pub fn write(&self, stream: &mut net::TcpStream) -> Result<usize, Error> {
@@ -66,14 +361,378 @@ impl RequestMessage {
         }
 
         stream.write_all(b"\r\n")?;
-        count += 2;
+        stream.write_all(&self.body)?;
+        count += 2 + self.body.len();
         return Ok(count);
     }
 }
 
+#[cfg(feature = "tokio")]
+impl RequestMessage {
+    pub async fn read_async<R: tokio::io::AsyncBufRead + Unpin>(
+        stream: R,
+        limits: &Limits,
+    ) -> Result<Self, HttpError> {
+        let mut reader = cube_core::bytes::AsyncByteReader::new(stream);
+        let line = reader
+            .read_until_exclusive_at_most(b"\r\n", limits.max_line_size.unwrap_or(usize::MAX))
+            .await
+            .map_err(|err| Self::limit_error(err, Status::URITooLong))?
+            .ok_or_else(Self::closed)?;
+
+        let mut message = Self::parse_line(&String::from_utf8_lossy(&line))?;
+        let mut size = 0;
+        let mut count = 0;
+
+        loop {
+            let max = limits
+                .max_header_size
+                .map_or(usize::MAX, |max| max.saturating_sub(size));
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", max)
+                .await
+                .map_err(|err| Self::limit_error(err, Status::RequestHeaderFieldsTooLarge))?
+                .ok_or_else(Self::truncated)?;
+
+            if line.is_empty() {
+                break;
+            }
+
+            size += line.len() + 2;
+            count += 1;
+
+            if limits.max_headers.is_some_and(|max| count > max) {
+                return Err(HttpError::new(Status::RequestHeaderFieldsTooLarge));
+            }
+
+            message.push_header(&String::from_utf8_lossy(&line))?;
+        }
+
+        return Ok(message);
+    }
+
+    pub async fn read_body_async<R: tokio::io::AsyncRead + Unpin>(
+        &mut self,
+        stream: R,
+        length: usize,
+    ) -> Result<(), Error> {
+        let mut reader = cube_core::bytes::AsyncByteReader::new(stream);
+        let buf = reader.read_to_vec_at_most(length).await?;
+
+        if buf.len() < length {
+            return Err(Error::from(format!(
+                "[cube::http::request] => expected {} bytes of body, found {}",
+                length,
+                buf.len()
+            )));
+        }
+
+        self.body = Bytes::from(buf);
+        return Ok(());
+    }
+
+    pub async fn read_chunked_async<R: tokio::io::AsyncBufRead + Unpin>(
+        &mut self,
+        stream: R,
+        max: Option<usize>,
+        limits: &Limits,
+    ) -> Result<(), HttpError> {
+        let mut reader = cube_core::bytes::AsyncByteReader::new(stream);
+        let mut body = BytesMut::new();
+
+        loop {
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", limits.max_line_size.unwrap_or(usize::MAX))
+                .await
+                .map_err(|err| Self::limit_error(err, Status::BadRequest))?
+                .ok_or_else(Self::truncated)?;
+            let size = Chunk::parse_size(&String::from_utf8_lossy(&line))?;
+
+            if size == 0 {
+                break;
+            }
+
+            if max.is_some_and(|max| body.len() + size > max) {
+                return Err(HttpError::new(Status::ContentTooLarge));
+            }
+
+            let buf = reader.read_to_vec_at_most(size).await?;
+
+            if buf.len() < size || reader.read::<2>().await? != *b"\r\n" {
+                return Err(HttpError::new(Status::BadRequest).message("incomplete chunk"));
+            }
+
+            body.put_slice(&buf);
+        }
+
+        let mut size = 0;
+        let mut count = 0;
+
+        loop {
+            let max = limits
+                .max_header_size
+                .map_or(usize::MAX, |max| max.saturating_sub(size));
+            let line = reader
+                .read_until_exclusive_at_most(b"\r\n", max)
+                .await
+                .map_err(|err| Self::limit_error(err, Status::RequestHeaderFieldsTooLarge))?
+                .ok_or_else(Self::truncated)?;
+
+            if line.is_empty() {
+                break;
+            }
+
+            size += line.len() + 2;
+            count += 1;
+
+            if limits.max_headers.is_some_and(|max| count > max) {
+                return Err(HttpError::new(Status::RequestHeaderFieldsTooLarge));
+            }
+
+            let (key, value) = Chunk::parse_trailer(&String::from_utf8_lossy(&line))?;
+            self.trailers.insert(key, value);
+        }
+
+        self.body = body.freeze();
+        return Ok(());
+    }
+
+    pub async fn write_async(&self, stream: &mut tokio::net::TcpStream) -> Result<usize, Error> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut head = format!(
+            "{} {} {}/{}\r\n",
+            self.method, self.path, self.protocol, self.protocol_v
+        );
+
+        for (key, value) in self.headers.iter() {
+            head.push_str(&format!("{}: {}\r\n", key, value));
+        }
+
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&self.body).await?;
+        return Ok(head.len() + self.body.len());
+    }
+}
+
 #[cfg(feature = "serde")]
 impl std::fmt::Display for RequestMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return write!(f, "{}", serde_json::to_string_pretty(self).unwrap());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{Limits, Method, Status};
+
+    fn message(version: &str, connection: Option<&str>) -> super::RequestMessage {
+        let mut headers = HashMap::new();
+
+        if let Some(v) = connection {
+            headers.insert(String::from("connection"), v.to_string());
+        }
+
+        return super::RequestMessage {
+            method: Method::Get,
+            path: String::from("/"),
+            protocol: String::from("http"),
+            protocol_v: version.to_string(),
+            headers,
+            body: bytes::Bytes::new(),
+            trailers: HashMap::new(),
+        };
+    }
+
+    #[test]
+    pub fn should_keep_alive() {
+        assert!(message("1.1", None).is_keep_alive());
+        assert!(message("1.0", Some("Keep-Alive")).is_keep_alive());
+        assert!(!message("1.0", None).is_keep_alive());
+        assert!(!message("1.1", Some("close")).is_keep_alive());
+    }
+
+    #[test]
+    pub fn should_parse_content_length() {
+        let mut message = message("1.1", None);
+        assert_eq!(message.content_length().unwrap(), None);
+
+        message
+            .headers
+            .insert(String::from("content-length"), String::from("42"));
+        assert_eq!(message.content_length().unwrap(), Some(42));
+
+        message
+            .headers
+            .insert(String::from("content-length"), String::from("42, 42"));
+        assert_eq!(message.content_length().unwrap(), Some(42));
+
+        message
+            .headers
+            .insert(String::from("content-length"), String::from("42, 7"));
+        assert!(message.content_length().is_err());
+
+        message
+            .headers
+            .insert(String::from("content-length"), String::from("-1"));
+        assert!(message.content_length().is_err());
+    }
+
+    #[test]
+    pub fn should_be_chunked() {
+        let mut message = message("1.1", None);
+        assert!(!message.is_chunked().unwrap());
+
+        message.headers.insert(
+            String::from("transfer-encoding"),
+            String::from("gzip, Chunked"),
+        );
+        assert!(message.is_chunked().unwrap());
+
+        message.headers.insert(
+            String::from("transfer-encoding"),
+            String::from("chunked, gzip"),
+        );
+        assert!(message.is_chunked().is_err());
+    }
+
+    #[test]
+    pub fn should_read() {
+        let mut raw = b"POST /users HTTP/1.1\r\nHost: localhost\r\nContent-Type:\"text/plain\"\r\nContent-Length: 5\r\n\r\nhello".as_slice();
+        let mut message = super::RequestMessage::read(&mut raw, &Limits::new()).unwrap();
+
+        assert_eq!(message.method, Method::Post);
+        assert_eq!(message.path, "/users");
+        assert_eq!(message.protocol, "http");
+        assert_eq!(message.protocol_v, "1.1");
+        assert_eq!(message.header("host"), Some("localhost"));
+        assert_eq!(message.header("content-type"), Some("text/plain"));
+
+        message.read_body(&mut raw, 5).unwrap();
+        assert_eq!(message.body.as_ref(), b"hello");
+
+        let mut raw = b"GET / HTTP/1.1\r\nHost: a\r\nhost: b\r\n\r\n".as_slice();
+        let message = super::RequestMessage::read(&mut raw, &Limits::new()).unwrap();
+        assert_eq!(message.header("Host"), Some("a, b"));
+
+        let mut raw = b"GET / HTTP/1.1\r\ninvalid\r\n\r\n".as_slice();
+        assert!(super::RequestMessage::read(&mut raw, &Limits::new()).is_err());
+    }
+
+    #[test]
+    pub fn should_read_pipelined() {
+        // a small buffer so `\r\n` spans two fills
+        let raw = b"POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nhelloGET /b HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let mut reader = std::io::BufReader::with_capacity(3, raw.as_slice());
+
+        let mut message = super::RequestMessage::read(&mut reader, &Limits::new()).unwrap();
+        assert_eq!(message.path, "/a");
+        message.read_body(&mut reader, 5).unwrap();
+        assert_eq!(message.body.as_ref(), b"hello");
+
+        let message = super::RequestMessage::read(&mut reader, &Limits::new()).unwrap();
+        assert_eq!(message.path, "/b");
+        assert_eq!(message.header("host"), Some("localhost"));
+    }
+
+    #[test]
+    pub fn should_refuse_invalid_headers() {
+        let mut raw = b"GET / HTTP/1.1\r\nX-Token: a!b\r\n\r\n".as_slice();
+        assert!(super::RequestMessage::read(&mut raw, &Limits::new()).is_ok());
+
+        for raw in [
+            b"GET / HTTP/1.1\r\n: 1\r\n\r\n".as_slice(),
+            b"GET / HTTP/1.1\r\nTransfer-Encoding : chunked\r\n\r\n",
+            b"GET / HTTP/1.1\r\n Host: localhost\r\n\r\n",
+            b"GET / HTTP/1.1\r\nX(Token): 1\r\n\r\n",
+            b"GET / HTTP/1.1\r\nX-Token: a\rb\r\n\r\n",
+            b"GET / HTTP/1.1\r\nX-Token: a\nb\r\n\r\n",
+            b"GET / HTTP/1.1\r\nX-Token: a\0b\r\n\r\n",
+        ] {
+            let mut raw = raw;
+            let err = super::RequestMessage::read(&mut raw, &Limits::new()).unwrap_err();
+            assert_eq!(err.status, Status::BadRequest);
+        }
+    }
+
+    #[test]
+    pub fn should_refuse_truncated_heads() {
+        let mut raw = b"".as_slice();
+        let err = super::RequestMessage::read(&mut raw, &Limits::new()).unwrap_err();
+        assert_eq!(err.message.as_deref(), Some("connection closed"));
+
+        for raw in [
+            b"GET / HT".as_slice(),
+            b"GET / HTTP/1.1\r\nHost: loc",
+            b"GET / HTTP/1.1\r\nHost: localhost\r\n",
+        ] {
+            let mut raw = raw;
+            let err = super::RequestMessage::read(&mut raw, &Limits::new()).unwrap_err();
+            assert_eq!(err.status, Status::BadRequest);
+            assert_ne!(err.message.as_deref(), Some("connection closed"));
+        }
+    }
+
+    #[test]
+    pub fn should_limit() {
+        let limits = Limits {
+            max_line_size: Some(16),
+            max_header_size: Some(32),
+            max_headers: Some(2),
+        };
+
+        let mut raw = b"GET /a HTTP/1.1\r\nA: 1\r\n\r\n".as_slice();
+        assert!(super::RequestMessage::read(&mut raw, &limits).is_ok());
+
+        let mut raw = b"GET /abcdefgh HTTP/1.1\r\n\r\n".as_slice();
+        let err = super::RequestMessage::read(&mut raw, &limits).unwrap_err();
+        assert_eq!(err.status, Status::URITooLong);
+
+        let mut raw = b"GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n".as_slice();
+        let err = super::RequestMessage::read(&mut raw, &limits).unwrap_err();
+        assert_eq!(err.status, Status::RequestHeaderFieldsTooLarge);
+
+        let mut raw = b"GET / HTTP/1.1\r\nA: 0123456789\r\nB: 0123456789abcdef\r\n\r\n".as_slice();
+        let err = super::RequestMessage::read(&mut raw, &limits).unwrap_err();
+        assert_eq!(err.status, Status::RequestHeaderFieldsTooLarge);
+    }
+    #[test]
+    pub fn should_read_chunked() {
+        let limits = Limits {
+            max_line_size: Some(8),
+            max_header_size: Some(16),
+            max_headers: Some(1),
+        };
+
+        let mut message = message("1.1", None);
+        let mut raw = b"5;a=b\r\nhello\r\n0\r\nA: 1\r\n\r\n".as_slice();
+        message.read_chunked(&mut raw, None, &limits).unwrap();
+        assert_eq!(message.body.as_ref(), b"hello");
+        assert_eq!(message.trailers["A"], "1");
+
+        let mut raw = b"5;extension=long\r\nhello\r\n0\r\n\r\n".as_slice();
+        let err = message.read_chunked(&mut raw, None, &limits).unwrap_err();
+        assert_eq!(err.status, Status::BadRequest);
+
+        let mut raw = b"5\r\nhello\r\n0\r\nA: 1\r\nB: 2\r\n\r\n".as_slice();
+        let err = message.read_chunked(&mut raw, None, &limits).unwrap_err();
+        assert_eq!(err.status, Status::RequestHeaderFieldsTooLarge);
+
+        let mut raw = b"5\r\nhello\r\n0\r\nA: 0123456789abcdef\r\n\r\n".as_slice();
+        let err = message.read_chunked(&mut raw, None, &limits).unwrap_err();
+        assert_eq!(err.status, Status::RequestHeaderFieldsTooLarge);
+
+        let mut raw = b"ffffffff\r\nhello".as_slice();
+        let err = message.read_chunked(&mut raw, None, &limits).unwrap_err();
+        assert_eq!(err.status, Status::BadRequest);
+
+        let mut raw = b"5\r\nhello\r\n0\r\n\r\n".as_slice();
+        let err = message
+            .read_chunked(&mut raw, Some(4), &limits)
+            .unwrap_err();
+        assert_eq!(err.status, Status::ContentTooLarge);
+    }
+}
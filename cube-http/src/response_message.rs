@@ -1,6 +1,7 @@
 use std::io::Write;
-use std::{collections::HashMap, net};
+use std::net;
 
+use bytes::Bytes;
 use cube_core::bytes::ByteReader;
 use cube_core::error::Error;
 
@@ -12,7 +13,10 @@ pub struct ResponseMessage {
     pub protocol: String,
     pub protocol_v: String,
     pub status: Status,
-    pub headers: HashMap<String, String>,
+    /// one name and value for each header line, in
+    /// the order they are sent, names can repeat
+    pub headers: Vec<(String, String)>,
+    pub body: Bytes,
 }
 
 impl ResponseMessage {
@@ -22,7 +26,8 @@ impl ResponseMessage {
             protocol: reader.read_utf8_until_exclusive(b"/")?,
             protocol_v: reader.read_utf8_until_exclusive(b" ")?,
             status: Status::try_from(reader.read_utf8_until_exclusive(b" ")?.as_str())?,
-            headers: HashMap::new(),
+            headers: vec![],
+            body: Bytes::new(),
         };
 
         reader.read_until(b"\r\n")?;
@@ -39,7 +44,7 @@ impl ResponseMessage {
                 value = value.strip_suffix("\"").unwrap().to_string();
             }
 
-            message.headers.insert(key, value);
+            message.headers.push((key, value));
 
             if reader.next_if(b"\r\n") {
                 break;
@@ -69,11 +74,63 @@ impl ResponseMessage {
         }
 
         stream.write_all(b"\r\n")?;
-        count += 2;
+        stream.write_all(&self.body)?;
+        count += 2 + self.body.len();
         return Ok(count);
     }
 }
 
+#[cfg(feature = "tokio")]
+impl ResponseMessage {
+    pub async fn read_async(stream: &mut tokio::net::TcpStream) -> Result<Self, Error> {
+        let mut reader = cube_core::bytes::AsyncByteReader::new(stream);
+        let mut message = Self {
+            protocol: reader.read_utf8_until_exclusive(b"/").await?,
+            protocol_v: reader.read_utf8_until_exclusive(b" ").await?,
+            status: Status::try_from(reader.read_utf8_until_exclusive(b" ").await?.as_str())?,
+            headers: vec![],
+            body: Bytes::new(),
+        };
+
+        reader.read_until(b"\r\n").await?;
+
+        // the empty line after the headers ends them
+        loop {
+            let line = reader.read_utf8_until_exclusive(b"\r\n").await?;
+            let Some((key, value)) = line.split_once(": ") else {
+                break;
+            };
+
+            let value = value.strip_prefix("\"").unwrap_or(value);
+            let value = value.strip_suffix("\"").unwrap_or(value);
+            message.headers.push((key.to_string(), value.to_string()));
+        }
+
+        return Ok(message);
+    }
+
+    pub async fn write_async(&self, stream: &mut tokio::net::TcpStream) -> Result<usize, Error> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut head = format!(
+            "{}/{} {} {}\r\n",
+            self.protocol,
+            self.protocol_v,
+            self.status.as_u16(),
+            self.status.reason(),
+        );
+
+        for (key, value) in self.headers.iter() {
+            head.push_str(&format!("{}: {}\r\n", key, value));
+        }
+
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&self.body).await?;
+        return Ok(head.len() + self.body.len());
+    }
+}
+
 #[cfg(feature = "serde")]
 impl std::fmt::Display for ResponseMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,225 @@
+use std::ops::{Deref, DerefMut};
+
+use bytes::Bytes;
+use cube_core::error::Error;
+
+use crate::{Codec, ContentType, HttpError, TextCodec};
+
+/// decode a raw request body into the body type
+/// of a `Request`, using the codec that supports
+/// its `Content-Type`
+pub trait FromBody: Sized {
+    fn from_body(content_type: Option<ContentType>, body: Bytes) -> Result<Self, HttpError>;
+}
+
+impl FromBody for Bytes {
+    fn from_body(_: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return Ok(body);
+    }
+}
+
+impl FromBody for Vec<u8> {
+    fn from_body(_: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return Ok(body.to_vec());
+    }
+}
+
+impl FromBody for String {
+    fn from_body(content_type: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return TextCodec.decode_request(content_type, &body);
+    }
+}
+
+impl FromBody for () {
+    fn from_body(_: Option<ContentType>, _: Bytes) -> Result<Self, HttpError> {
+        return Ok(());
+    }
+}
+
+/// encode the body of a `Response` into raw bytes,
+/// along with the content type it is sent as when
+/// the handler didn't set one
+pub trait IntoBody {
+    fn content_type(&self) -> ContentType;
+    fn into_body(self) -> Result<Bytes, Error>;
+}
+
+impl IntoBody for Bytes {
+    fn content_type(&self) -> ContentType {
+        return ContentType::OctetStream;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return Ok(self);
+    }
+}
+
+impl IntoBody for Vec<u8> {
+    fn content_type(&self) -> ContentType {
+        return ContentType::OctetStream;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return Ok(Bytes::from(self));
+    }
+}
+
+impl IntoBody for String {
+    fn content_type(&self) -> ContentType {
+        return ContentType::PlainText;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return Ok(Bytes::from(self));
+    }
+}
+
+impl IntoBody for &'static str {
+    fn content_type(&self) -> ContentType {
+        return ContentType::PlainText;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return Ok(Bytes::from(self));
+    }
+}
+
+impl IntoBody for () {
+    fn content_type(&self) -> ContentType {
+        return ContentType::OctetStream;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return Ok(Bytes::new());
+    }
+}
+
+/// a body (de)serialized with the `JsonCodec`
+#[derive(Debug, Clone, Default)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FromBody for Json<T> {
+    fn from_body(content_type: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return Ok(Self(crate::JsonCodec.decode_request(content_type, &body)?));
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoBody for Json<T> {
+    fn content_type(&self) -> ContentType {
+        return ContentType::Json;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return crate::JsonCodec.encode(&self.0);
+    }
+}
+
+/// a body (de)serialized with the `FormCodec`
+#[derive(Debug, Clone, Default)]
+pub struct Form<T>(pub T);
+
+impl<T> Form<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for Form<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for Form<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FromBody for Form<T> {
+    fn from_body(content_type: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return Ok(Self(crate::FormCodec.decode_request(content_type, &body)?));
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoBody for Form<T> {
+    fn content_type(&self) -> ContentType {
+        return ContentType::FormUrlEncoded;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return crate::FormCodec.encode(&self.0);
+    }
+}
+
+/// a body decoded with the serde codec picked by its
+/// `Content-Type`, either JSON or a urlencoded form,
+/// and sent as JSON
+#[derive(Debug, Clone, Default)]
+pub struct Payload<T>(pub T);
+
+impl<T> Payload<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for Payload<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for Payload<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned + 'static> FromBody for Payload<T> {
+    fn from_body(content_type: Option<ContentType>, body: Bytes) -> Result<Self, HttpError> {
+        return Ok(Self(
+            crate::Codecs::serde().decode_request(content_type, &body)?,
+        ));
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoBody for Payload<T> {
+    fn content_type(&self) -> ContentType {
+        return ContentType::Json;
+    }
+
+    fn into_body(self) -> Result<Bytes, Error> {
+        return crate::JsonCodec.encode(&self.0);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    io::{self, Read},
+    net,
+    time::{Duration, Instant},
+};
+
+/// Deadline
+///
+/// reads from a connection until a point in time,
+/// after which reads fail with `TimedOut` no matter
+/// how slowly the bytes keep arriving
+///
+/// the read timeout of the socket is set to the time left
+/// before every read, so no read waits past the deadline
+pub(crate) struct Deadline {
+    stream: net::TcpStream,
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    pub fn new(stream: net::TcpStream) -> Self {
+        return Self { stream, at: None };
+    }
+
+    /// the earliest of `at` and `timeout` from now
+    pub fn earliest(at: Option<Instant>, timeout: Option<Duration>) -> Option<Instant> {
+        let after = timeout.map(|v| Instant::now() + v);
+
+        return match (at, after) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// read until `at`, or with no deadline when `None`
+    pub fn set(&mut self, at: Option<Instant>) -> io::Result<()> {
+        self.at = at;
+
+        return match at {
+            None => self.stream.set_read_timeout(None),
+            Some(at) => self.arm(at.saturating_duration_since(Instant::now())),
+        };
+    }
+
+    fn arm(&mut self, remaining: Duration) -> io::Result<()> {
+        // a zero timeout is rejected, the next read fails instead
+        if remaining.is_zero() {
+            return Ok(());
+        }
+
+        return self.stream.set_read_timeout(Some(remaining));
+    }
+
+    pub fn is_expired(&self) -> bool {
+        return self.at.is_some_and(|at| Instant::now() >= at);
+    }
+
+    pub fn get_ref(&self) -> &net::TcpStream {
+        return &self.stream;
+    }
+
+    pub fn get_mut(&mut self) -> &mut net::TcpStream {
+        return &mut self.stream;
+    }
+
+    pub fn into_inner(self) -> net::TcpStream {
+        return self.stream;
+    }
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(at) = self.at {
+            let remaining = at.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+
+            self.arm(remaining)?;
+        }
+
+        return match self.stream.read(buf) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                Err(io::ErrorKind::TimedOut.into())
+            }
+            v => v,
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{self, Read, Write},
+        net,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    pub fn should_time_out() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        client.write_all(b"a").unwrap();
+
+        let at = Instant::now() + Duration::from_millis(50);
+        let mut deadline = super::Deadline::new(stream);
+        deadline.set(Some(at)).unwrap();
+        let mut buf = [0; 1];
+
+        assert_eq!(deadline.read(&mut buf).unwrap(), 1);
+        assert_eq!(
+            deadline.read(&mut buf).unwrap_err().kind(),
+            io::ErrorKind::TimedOut
+        );
+        assert!(deadline.is_expired());
+    }
+
+    #[test]
+    pub fn should_cut_off_a_trickle() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        let start = Instant::now();
+        let mut deadline = super::Deadline::new(stream);
+        deadline
+            .set(Some(start + Duration::from_millis(200)))
+            .unwrap();
+
+        // a byte early on and then nothing, while the connection stays open
+        let trickle = std::thread::spawn(move || {
+            client.write_all(b"a").unwrap();
+            std::thread::sleep(Duration::from_millis(80));
+            client.write_all(b"b").unwrap();
+            std::thread::sleep(Duration::from_millis(400));
+        });
+
+        let mut buf = [0; 1];
+        let err = loop {
+            if let Err(err) = deadline.read(&mut buf) {
+                break err;
+            }
+        };
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_millis(250));
+        trickle.join().unwrap();
+    }
+}
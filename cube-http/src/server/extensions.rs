@@ -0,0 +1,69 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+/// values of any type attached to a request,
+/// keyed by their type
+#[derive(Clone, Default)]
+pub struct Extensions {
+    data: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        return Self {
+            data: HashMap::new(),
+        };
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.data.len();
+    }
+
+    pub fn has<T: Send + Sync + 'static>(&self) -> bool {
+        return self.data.contains_key(&TypeId::of::<T>());
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        return self
+            .data
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_ref::<T>());
+    }
+
+    /// a shared reference to the value, that
+    /// outlives these extensions
+    pub fn get_shared<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        return self
+            .data
+            .get(&TypeId::of::<T>())?
+            .clone()
+            .downcast::<T>()
+            .ok();
+    }
+
+    pub fn set<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.data.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn del<T: Send + Sync + 'static>(&mut self) {
+        self.data.remove(&TypeId::of::<T>());
+    }
+
+    /// copy every value of `other` into these extensions
+    pub fn extend(&mut self, other: &Self) {
+        for (key, value) in &other.data {
+            self.data.insert(*key, value.clone());
+        }
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Extensions({})", self.data.len());
+    }
+}
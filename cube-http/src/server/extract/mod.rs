@@ -0,0 +1,223 @@
+mod path;
+pub use path::*;
+
+mod query;
+pub use query::*;
+
+mod state;
+pub use state::*;
+
+mod typed_header;
+pub use typed_header::*;
+
+use bytes::Bytes;
+use cube_url::{Params, Url};
+
+use crate::{
+    Headers, HttpError, Method,
+    server::{Extensions, FromBody, Request},
+};
+
+/// Extractor
+///
+/// builds a handler argument from the request,
+/// failing with the error the server responds with
+pub trait FromRequest: Sized {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError>;
+}
+
+/// the whole request with its body decoded
+impl<T: FromBody> FromRequest for Request<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return req.clone().decode::<T>();
+    }
+}
+
+impl FromRequest for Method {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.method);
+    }
+}
+
+impl FromRequest for Url {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.url.clone());
+    }
+}
+
+impl FromRequest for Params {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.params().clone());
+    }
+}
+
+impl FromRequest for cube_url::Query {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.url.query().clone());
+    }
+}
+
+impl FromRequest for Headers {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.headers.clone());
+    }
+}
+
+impl FromRequest for Extensions {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.extensions.clone());
+    }
+}
+
+/// the raw body, empty when there is none
+impl FromRequest for Bytes {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(req.body.clone().unwrap_or_default());
+    }
+}
+
+/// the body as text, empty when there is none
+impl FromRequest for String {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return String::from_body(req.content_type(), req.body.clone().unwrap_or_default());
+    }
+}
+
+/// `None` instead of failing
+impl<T: FromRequest> FromRequest for Option<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Ok(T::from_request(req).ok());
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FromRequest for crate::server::Json<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Self::from_body(req.content_type(), body(req)?);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FromRequest for crate::server::Form<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Self::from_body(req.content_type(), body(req)?);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned + 'static> FromRequest
+    for crate::server::Payload<T>
+{
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return Self::from_body(req.content_type(), body(req)?);
+    }
+}
+
+#[cfg(feature = "serde")]
+fn body(req: &Request<Bytes>) -> Result<Bytes, HttpError> {
+    return match &req.body {
+        Some(v) => Ok(v.clone()),
+        None => Err(HttpError::new(crate::Status::BadRequest).message("missing request body")),
+    };
+}
+
+/// deserialize string pairs into `T` through the urlencoded
+/// format, so each value is parsed as the type `T` expects
+#[cfg(feature = "serde")]
+fn deserialize<S: serde::Serialize, T: serde::de::DeserializeOwned>(
+    pairs: &S,
+) -> Result<T, cube_core::error::Error> {
+    let encoded = serde_urlencoded::to_string(pairs)
+        .map_err(|err| cube_core::error::Error::from(err.to_string()))?;
+
+    return serde_urlencoded::from_str(&encoded)
+        .map_err(|err| cube_core::error::Error::from(err.to_string()));
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Header, Headers, Method, Status,
+        server::{Extensions, Request, Response, router::Router},
+    };
+
+    use super::{Authorization, State, TypedHeader};
+
+    fn request(url: &str) -> Request<Bytes> {
+        return Request {
+            method: Method::Get,
+            url: Url::parse(url).unwrap(),
+            headers: Headers::new(),
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        };
+    }
+
+    #[test]
+    pub fn should_extract() {
+        let mut router = Router::new();
+        router.get(
+            "/users",
+            |State(name): State<&'static str>, auth: Option<TypedHeader<Authorization>>| {
+                return match auth {
+                    None => (Status::Unauthorized, String::new()),
+                    Some(v) => (Status::Ok, format!("{} {}", name, v.bearer().unwrap())),
+                };
+            },
+        );
+
+        let mut req = request("http://localhost/users");
+        req.extensions.set("test");
+
+        let res = router.dispatch(req.clone());
+        assert_eq!(res.status, Status::Unauthorized);
+
+        req.headers
+            .set("Authorization", &Header::Raw(String::from("Bearer abc")));
+
+        let res: Response<Bytes> = router.dispatch(req);
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(res.body.unwrap(), "test abc");
+
+        let res = router.dispatch(request("http://localhost/users"));
+        assert_eq!(res.status, Status::InternalServerError);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn should_extract_path_and_query() {
+        use super::{Path, Query};
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct User {
+            user: u64,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Page {
+            size: Option<u32>,
+        }
+
+        let mut router = Router::new();
+        router.get(
+            "/users/{user}",
+            |Path(path): Path<User>, Query(page): Query<Page>| {
+                return format!("{} {}", path.user, page.size.unwrap_or(10));
+            },
+        );
+
+        let res = router.dispatch(request("http://localhost/users/1?size=5"));
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(res.body.unwrap(), "1 5");
+
+        let res = router.dispatch(request("http://localhost/users/test"));
+        assert_eq!(res.status, Status::BadRequest);
+
+        let res = router.dispatch(request("http://localhost/users/1?size=big"));
+        assert_eq!(res.status, Status::BadRequest);
+    }
+}
@@ -0,0 +1,43 @@
+use std::ops::{Deref, DerefMut};
+
+/// the params captured from the path,
+/// deserialized into `T`
+///
+/// Example
+/// -------
+/// `/users/{user_id}/orgs/{org}`
+/// =>
+/// `struct Params { user_id: u64, org: String }`
+#[derive(Debug, Clone)]
+pub struct Path<T>(pub T);
+
+impl<T> Path<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned> super::FromRequest for Path<T> {
+    fn from_request(req: &crate::server::Request<bytes::Bytes>) -> Result<Self, crate::HttpError> {
+        return match super::deserialize(req.params()) {
+            Ok(v) => Ok(Self(v)),
+            Err(err) => Err(crate::HttpError::new(crate::Status::BadRequest)
+                .message(&format!("invalid path params: {}", err))),
+        };
+    }
+}
@@ -0,0 +1,37 @@
+use std::ops::{Deref, DerefMut};
+
+/// the query of the url,
+/// deserialized into `T`
+#[derive(Debug, Clone)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned> super::FromRequest for Query<T> {
+    fn from_request(req: &crate::server::Request<bytes::Bytes>) -> Result<Self, crate::HttpError> {
+        return match super::deserialize(req.url.query()) {
+            Ok(v) => Ok(Self(v)),
+            Err(err) => Err(crate::HttpError::new(crate::Status::BadRequest)
+                .message(&format!("invalid query: {}", err))),
+        };
+    }
+}
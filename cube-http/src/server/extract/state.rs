@@ -0,0 +1,39 @@
+use std::{ops::Deref, sync::Arc};
+
+use bytes::Bytes;
+
+use crate::{
+    HttpError, Status,
+    server::{Request, extract::FromRequest},
+};
+
+/// the application state of type `T` registered
+/// on the `Router` or `Server`
+#[derive(Debug, Clone)]
+pub struct State<T>(pub Arc<T>);
+
+impl<T> State<T> {
+    pub fn into_inner(self) -> Arc<T> {
+        return self.0;
+    }
+}
+
+impl<T> Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+/// missing state is a mistake in how the
+/// server was built, so it fails with `500`
+impl<T: Send + Sync + 'static> FromRequest for State<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return match req.extensions.get_shared::<T>() {
+            Some(v) => Ok(Self(v)),
+            None => Err(HttpError::new(Status::InternalServerError)
+                .message(&format!("missing state \"{}\"", std::any::type_name::<T>()))),
+        };
+    }
+}
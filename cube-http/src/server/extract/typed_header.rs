@@ -0,0 +1,103 @@
+use std::ops::Deref;
+
+use bytes::Bytes;
+use cube_core::error::Error;
+
+use crate::{
+    ContentType, HttpError, Status,
+    server::{Request, extract::FromRequest},
+};
+
+/// a header value parsed from its name
+pub trait FromHeader: Sized {
+    const NAME: &'static str;
+
+    fn from_header(value: &str) -> Result<Self, Error>;
+}
+
+/// a single header parsed into `T`
+#[derive(Debug, Clone)]
+pub struct TypedHeader<T>(pub T);
+
+impl<T> TypedHeader<T> {
+    pub fn into_inner(self) -> T {
+        return self.0;
+    }
+}
+
+impl<T> Deref for TypedHeader<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T: FromHeader> FromRequest for TypedHeader<T> {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        let Some(value) = req.headers.get(T::NAME) else {
+            return Err(HttpError::new(Status::BadRequest)
+                .message(&format!("missing header \"{}\"", T::NAME)));
+        };
+
+        return match T::from_header(&value.to_string()) {
+            Ok(v) => Ok(Self(v)),
+            Err(err) => Err(HttpError::new(Status::BadRequest).message(&format!(
+                "invalid header \"{}\": {}",
+                T::NAME,
+                err
+            ))),
+        };
+    }
+}
+
+impl FromHeader for ContentType {
+    const NAME: &'static str = "Content-Type";
+
+    fn from_header(value: &str) -> Result<Self, Error> {
+        return ContentType::try_from(value);
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Host
+#[derive(Debug, Clone)]
+pub struct Host(pub String);
+
+impl FromHeader for Host {
+    const NAME: &'static str = "Host";
+
+    fn from_header(value: &str) -> Result<Self, Error> {
+        return Ok(Self(value.to_string()));
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/User-Agent
+#[derive(Debug, Clone)]
+pub struct UserAgent(pub String);
+
+impl FromHeader for UserAgent {
+    const NAME: &'static str = "User-Agent";
+
+    fn from_header(value: &str) -> Result<Self, Error> {
+        return Ok(Self(value.to_string()));
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Authorization
+#[derive(Debug, Clone)]
+pub struct Authorization(pub String);
+
+impl Authorization {
+    /// the credentials of a `Bearer` token
+    pub fn bearer(&self) -> Option<&str> {
+        return self.0.strip_prefix("Bearer ").map(|v| v.trim());
+    }
+}
+
+impl FromHeader for Authorization {
+    const NAME: &'static str = "Authorization";
+
+    fn from_header(value: &str) -> Result<Self, Error> {
+        return Ok(Self(value.to_string()));
+    }
+}
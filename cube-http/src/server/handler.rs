@@ -0,0 +1,111 @@
+use std::marker::PhantomData;
+
+use bytes::Bytes;
+
+use crate::server::{FromBody, IntoBody, IntoResponse, Request, Response, extract::FromRequest};
+
+/// Handler
+///
+/// a function the router calls with each matched request,
+/// either `Fn(&Request<ReqBody>, &mut Response<ResBody>)` or
+/// a function of up to 8 extractors that returns anything
+/// implementing `IntoResponse`
+///
+/// Example
+/// -------
+/// `|Path(user): Path<User>, Json(body): Json<Update>| Status::Ok`
+pub trait Handler<Args>: Send + Sync + 'static {
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes>;
+}
+
+/// the async counterpart of `Handler`, for
+/// functions of extractors that return a future
+#[cfg(feature = "tokio")]
+pub trait AsyncHandler<Args>: Send + Sync + 'static {
+    fn call(&self, req: Request<Bytes>) -> crate::server::router::BoxFuture<'_, Response<Bytes>>;
+}
+
+/// marks a `Handler` that writes into a `Response`
+/// instead of returning one
+pub struct Responder<ReqBody, ResBody> {
+    __phantom__: PhantomData<fn() -> (ReqBody, ResBody)>,
+}
+
+impl<F, ReqBody, ResBody> Handler<Responder<ReqBody, ResBody>> for F
+where
+    F: Fn(&Request<ReqBody>, &mut Response<ResBody>) + Send + Sync + 'static,
+    ReqBody: FromBody,
+    ResBody: IntoBody,
+{
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes> {
+        let req = match req.decode::<ReqBody>() {
+            Ok(v) => v,
+            Err(err) => return err.into_response(),
+        };
+
+        let mut res = Response::<ResBody>::new();
+        (self)(&req, &mut res);
+        return res.into_response();
+    }
+}
+
+macro_rules! handler {
+    ($($t:ident $v:ident),*) => {
+        #[allow(unused_variables)]
+        impl<F, R, $($t,)*> Handler<($($t,)*)> for F
+        where
+            F: Fn($($t),*) -> R + Send + Sync + 'static,
+            R: IntoResponse,
+            $($t: FromRequest,)*
+        {
+            fn call(&self, req: Request<Bytes>) -> Response<Bytes> {
+                $(
+                    let $v = match $t::from_request(&req) {
+                        Ok(v) => v,
+                        Err(err) => return err.into_response(),
+                    };
+                )*
+
+                return (self)($($v),*).into_response();
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        #[allow(unused_variables)]
+        impl<F, Fut, R, $($t,)*> AsyncHandler<($($t,)*)> for F
+        where
+            F: Fn($($t),*) -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = R> + Send + 'static,
+            R: IntoResponse,
+            $($t: FromRequest,)*
+        {
+            fn call(
+                &self,
+                req: Request<Bytes>,
+            ) -> crate::server::router::BoxFuture<'_, Response<Bytes>> {
+                $(
+                    let $v = match $t::from_request(&req) {
+                        Ok(v) => v,
+                        Err(err) => {
+                            let res = err.into_response();
+                            return Box::pin(async move { res });
+                        }
+                    };
+                )*
+
+                let fut = (self)($($v),*);
+                return Box::pin(async move { fut.await.into_response() });
+            }
+        }
+    };
+}
+
+handler!();
+handler!(T1 t1);
+handler!(T1 t1, T2 t2);
+handler!(T1 t1, T2 t2, T3 t3);
+handler!(T1 t1, T2 t2, T3 t3, T4 t4);
+handler!(T1 t1, T2 t2, T3 t3, T4 t4, T5 t5);
+handler!(T1 t1, T2 t2, T3 t3, T4 t4, T5 t5, T6 t6);
+handler!(T1 t1, T2 t2, T3 t3, T4 t4, T5 t5, T6 t6, T7 t7);
+handler!(T1 t1, T2 t2, T3 t3, T4 t4, T5 t5, T6 t6, T7 t7, T8 t8);
@@ -0,0 +1,231 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::server::log::AccessEvent;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Log Format
+///
+/// how an `AccessEvent` is written as a single line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// https://httpd.apache.org/docs/current/logs.html#common
+    Common,
+
+    /// the common format followed by the referer and user agent
+    /// https://httpd.apache.org/docs/current/logs.html#combined
+    Combined,
+
+    /// one JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    pub fn format(&self, event: &AccessEvent) -> String {
+        return match self {
+            Self::Common => Self::common(event),
+            Self::Combined => format!(
+                "{} \"{}\" \"{}\"",
+                Self::common(event),
+                quote(event.referer.as_deref().unwrap_or("-")),
+                quote(event.user_agent.as_deref().unwrap_or("-")),
+            ),
+            Self::Json => Self::json(event),
+        };
+    }
+
+    fn common(event: &AccessEvent) -> String {
+        let (year, month, day, hour, minute, second) = civil(event.time);
+
+        // `-` stands for a request line that was never read
+        let request = match (&event.method, &event.path, &event.protocol) {
+            (Some(method), Some(path), Some(protocol)) => {
+                format!("{} {} {}", method, quote(path), quote(protocol))
+            }
+            _ => String::from("-"),
+        };
+
+        return format!(
+            "{} - - [{:02}/{}/{}:{:02}:{:02}:{:02} +0000] \"{}\" {} {}",
+            event.peer.ip(),
+            day,
+            MONTHS[month as usize - 1],
+            year,
+            hour,
+            minute,
+            second,
+            request,
+            event.status.as_u16(),
+            match event.bytes {
+                0 => String::from("-"),
+                v => v.to_string(),
+            },
+        );
+    }
+
+    fn json(event: &AccessEvent) -> String {
+        let (year, month, day, hour, minute, second) = civil(event.time);
+        let optional = |value: &Option<String>| match value {
+            None => String::from("null"),
+            Some(v) => format!("\"{}\"", escape(v)),
+        };
+
+        return format!(
+            "{{\"time\":\"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z\",\"method\":{},\"path\":{},\"protocol\":{},\"status\":{},\"bytes\":{},\"duration_ms\":{:.3},\"peer\":\"{}\",\"user_agent\":{},\"referer\":{}}}",
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            optional(&event.method.map(|v| v.to_string())),
+            optional(&event.path),
+            optional(&event.protocol),
+            event.status.as_u16(),
+            event.bytes,
+            event.duration.as_secs_f64() * 1000.0,
+            event.peer,
+            optional(&event.user_agent),
+            optional(&event.referer),
+        );
+    }
+}
+
+/// escape quotes and control characters inside a quoted log
+/// field, so a value can't end the line and forge another
+fn quote(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                for b in c.to_string().bytes() {
+                    escaped.push_str(&format!("\\x{:02x}", b));
+                }
+            }
+            c => escaped.push(c),
+        };
+    }
+
+    return escaped;
+}
+
+/// escape a JSON string value
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        };
+    }
+
+    return escaped;
+}
+
+/// the UTC date and time of `time`
+/// https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil(time: SystemTime) -> (u64, u64, u64, u64, u64, u64) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let (days, rem) = (secs / 86400, secs % 86400);
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as u64;
+
+    return (year, month, day, rem / 3600, rem % 3600 / 60, rem % 60);
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::{Method, Status, server::log::AccessEvent};
+
+    use super::LogFormat;
+
+    fn event() -> AccessEvent {
+        return AccessEvent {
+            time: UNIX_EPOCH + Duration::from_secs(971186136),
+            method: Some(Method::Get),
+            path: Some(String::from("/apache_pb.gif")),
+            protocol: Some(String::from("HTTP/1.0")),
+            status: Status::Ok,
+            bytes: 2326,
+            duration: Duration::from_micros(1500),
+            peer: "127.0.0.1:5000".parse().unwrap(),
+            user_agent: Some(String::from("Mozilla/4.08 \"test\"")),
+            referer: None,
+        };
+    }
+
+    #[test]
+    pub fn should_format_common() {
+        assert_eq!(
+            LogFormat::Common.format(&event()),
+            "127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] \"GET /apache_pb.gif HTTP/1.0\" 200 2326"
+        );
+
+        assert_eq!(
+            LogFormat::Combined.format(&event()),
+            "127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] \"GET /apache_pb.gif HTTP/1.0\" 200 2326 \"-\" \"Mozilla/4.08 \\\"test\\\"\""
+        );
+    }
+
+    #[test]
+    pub fn should_escape_control_characters() {
+        let mut event = event();
+        event.user_agent = Some(String::from("a\n127.0.0.1 - - \"GET /admin\"\x1b"));
+
+        let line = LogFormat::Combined.format(&event);
+        assert!(!line.contains('\n'));
+        assert!(line.ends_with("\"a\\x0a127.0.0.1 - - \\\"GET /admin\\\"\\x1b\""));
+    }
+
+    #[test]
+    pub fn should_format_json() {
+        assert_eq!(
+            LogFormat::Json.format(&event()),
+            "{\"time\":\"2000-10-10T13:55:36Z\",\"method\":\"GET\",\"path\":\"/apache_pb.gif\",\"protocol\":\"HTTP/1.0\",\"status\":200,\"bytes\":2326,\"duration_ms\":1.500,\"peer\":\"127.0.0.1:5000\",\"user_agent\":\"Mozilla/4.08 \\\"test\\\"\",\"referer\":null}"
+        );
+    }
+
+    #[test]
+    pub fn should_format_aborted() {
+        let mut event = event();
+        event.method = None;
+        event.path = None;
+        event.protocol = None;
+        event.status = Status::RequestTimeout;
+        event.bytes = 0;
+
+        assert_eq!(
+            LogFormat::Common.format(&event),
+            "127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] \"-\" 408 -"
+        );
+        assert!(
+            LogFormat::Json
+                .format(&event)
+                .contains("\"method\":null,\"path\":null,\"protocol\":null,\"status\":408")
+        );
+    }
+}
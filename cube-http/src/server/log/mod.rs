@@ -0,0 +1,87 @@
+mod format;
+pub use format::*;
+
+mod writer;
+pub use writer::*;
+
+mod rotating_file;
+pub use rotating_file::*;
+
+use std::{
+    net,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{Method, RequestMessage, Status};
+
+/// Access Event
+///
+/// what the server knows about a request
+/// once its response has been written, the
+/// request line is `None` when the server
+/// answered before it could be read, such
+/// as with `408`, `414` or `503`
+#[derive(Debug, Clone)]
+pub struct AccessEvent {
+    /// when the request started arriving
+    pub time: SystemTime,
+    pub method: Option<Method>,
+
+    /// the request target as sent by the client
+    pub path: Option<String>,
+
+    /// the protocol and version, such as `HTTP/1.1`
+    pub protocol: Option<String>,
+    pub status: Status,
+
+    /// the number of body bytes written
+    pub bytes: usize,
+
+    /// from the start of the request to
+    /// the end of the response
+    pub duration: Duration,
+    pub peer: net::SocketAddr,
+    pub user_agent: Option<String>,
+    pub referer: Option<String>,
+}
+
+impl AccessEvent {
+    pub(crate) fn new(
+        message: Option<&RequestMessage>,
+        status: Status,
+        bytes: usize,
+        start: Instant,
+        peer: net::SocketAddr,
+    ) -> Self {
+        let duration = start.elapsed();
+
+        return Self {
+            time: SystemTime::now() - duration,
+            method: message.map(|m| m.method),
+            path: message.map(|m| m.path.clone()),
+            protocol: message.map(|m| format!("{}/{}", m.protocol.to_uppercase(), m.protocol_v)),
+            status,
+            bytes,
+            duration,
+            peer,
+            user_agent: message
+                .and_then(|m| m.header("User-Agent"))
+                .map(String::from),
+            referer: message.and_then(|m| m.header("Referer")).map(String::from),
+        };
+    }
+}
+
+/// Access Log
+///
+/// receives an event for every response
+/// the server writes
+pub trait AccessLog: Send + Sync {
+    fn log(&self, event: &AccessEvent);
+}
+
+impl<F: Fn(&AccessEvent) + Send + Sync> AccessLog for F {
+    fn log(&self, event: &AccessEvent) {
+        self(event);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Rotating File
+///
+/// a file opened for appending that is renamed with a
+/// `.1` suffix once it grows past `max_size` bytes,
+/// shifting older files up to `max_files` of them
+///
+/// Example
+/// -------
+/// `access.log` => `access.log.1` => `access.log.2`
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: fs::File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open<P: AsRef<Path>>(path: P, max_size: u64) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::append(&path)?;
+        let size = file.metadata()?.len();
+
+        return Ok(Self {
+            path,
+            max_size,
+            max_files: 5,
+            file,
+            size,
+        });
+    }
+
+    /// the number of rotated files to keep
+    pub fn max_files(&mut self, max_files: usize) -> &mut Self {
+        self.max_files = max_files;
+        return self;
+    }
+
+    fn append(path: &Path) -> io::Result<fs::File> {
+        return fs::OpenOptions::new().create(true).append(true).open(path);
+    }
+
+    fn rotated(&self, i: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", i));
+        return PathBuf::from(name);
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            self.file.set_len(0)?;
+            self.size = 0;
+            return Ok(());
+        }
+
+        for i in (1..self.max_files).rev() {
+            let _ = fs::rename(self.rotated(i), self.rotated(i + 1));
+        }
+
+        fs::rename(&self.path, self.rotated(1))?;
+        self.file = Self::append(&self.path)?;
+        self.size = 0;
+        return Ok(());
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        let size = self.file.write(buf)?;
+        self.size += size as u64;
+        return Ok(size);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.file.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, io::Write};
+
+    #[test]
+    pub fn should_rotate() {
+        let dir = std::env::temp_dir().join(format!("cube-rotate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("access.log");
+        let mut file = super::RotatingFile::open(&path, 10).unwrap();
+        file.max_files(2);
+
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "dddddddd\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("access.log.1")).unwrap(),
+            "cccccccc\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("access.log.2")).unwrap(),
+            "bbbbbbbb\n"
+        );
+        assert!(!dir.join("access.log.3").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
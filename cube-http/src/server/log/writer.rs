@@ -0,0 +1,200 @@
+use std::{
+    io::{self, Write},
+    marker::PhantomData,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::server::log::{AccessEvent, AccessLog, LogFormat, RotatingFile};
+
+/// Log Writer
+///
+/// writes each `AccessEvent` as a line in
+/// the given format to stdout, a file or
+/// any other writer
+///
+/// lines are handed to a thread that owns the
+/// writer, so a slow disk never blocks the
+/// threads or tasks answering requests, and
+/// those still queued are written on drop
+///
+/// the queue holds at most `capacity` lines, once
+/// the writer falls that far behind new lines are
+/// dropped and counted rather than kept in memory
+pub struct LogWriter<W: Write + Send + 'static> {
+    format: LogFormat,
+    sender: Option<mpsc::SyncSender<String>>,
+    thread: Option<thread::JoinHandle<()>>,
+    dropped: AtomicUsize,
+    out: PhantomData<fn(W)>,
+}
+
+impl<W: Write + Send + 'static> LogWriter<W> {
+    /// the number of lines queued by `LogWriter::new`
+    pub const CAPACITY: usize = 8192;
+
+    pub fn new(format: LogFormat, out: W) -> Self {
+        return Self::with_capacity(format, out, Self::CAPACITY);
+    }
+
+    /// queue at most `capacity` lines for the writer
+    pub fn with_capacity(format: LogFormat, mut out: W, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<String>(capacity);
+        let thread = thread::Builder::new()
+            .name(String::from("cube-access-log"))
+            .spawn(move || {
+                while let Ok(line) = receiver.recv() {
+                    let _ = out.write_all(line.as_bytes());
+
+                    // flush once the lines that came in together are written
+                    for line in receiver.try_iter() {
+                        let _ = out.write_all(line.as_bytes());
+                    }
+
+                    let _ = out.flush();
+                }
+            })
+            .expect("[cube::http::log] => failed to spawn the writer thread");
+
+        return Self {
+            format,
+            sender: Some(sender),
+            thread: Some(thread),
+            dropped: AtomicUsize::new(0),
+            out: PhantomData,
+        };
+    }
+
+    /// the number of lines dropped while the queue was full
+    pub fn dropped(&self) -> usize {
+        return self.dropped.load(Ordering::Relaxed);
+    }
+}
+
+impl LogWriter<io::Stdout> {
+    pub fn stdout(format: LogFormat) -> Self {
+        return Self::new(format, io::stdout());
+    }
+}
+
+impl LogWriter<RotatingFile> {
+    /// append to the file at `path`, rotating it
+    /// once it grows past `max_size` bytes
+    pub fn file<P: AsRef<Path>>(format: LogFormat, path: P, max_size: u64) -> io::Result<Self> {
+        return Ok(Self::new(format, RotatingFile::open(path, max_size)?));
+    }
+}
+
+impl<W: Write + Send + 'static> AccessLog for LogWriter<W> {
+    fn log(&self, event: &AccessEvent) {
+        let mut line = self.format.format(event);
+        line.push('\n');
+
+        if let Some(sender) = &self.sender
+            && let Err(mpsc::TrySendError::Full(_)) = sender.try_send(line)
+        {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for LogWriter<W> {
+    fn drop(&mut self) {
+        // closing the channel ends the thread once it is drained
+        self.sender = None;
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex, mpsc},
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use crate::{
+        Method, Status,
+        server::log::{AccessEvent, AccessLog, LogFormat},
+    };
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            return self.0.lock().unwrap().write(buf);
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            return Ok(());
+        }
+    }
+
+    /// blocks every write until the gate is dropped
+    struct Gate(mpsc::Receiver<()>);
+
+    impl Write for Gate {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let _ = self.0.recv();
+            return Ok(buf.len());
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            return Ok(());
+        }
+    }
+
+    fn event() -> AccessEvent {
+        return AccessEvent {
+            time: UNIX_EPOCH + Duration::from_secs(971186136),
+            method: Some(Method::Get),
+            path: Some(String::from("/")),
+            protocol: Some(String::from("HTTP/1.1")),
+            status: Status::Ok,
+            bytes: 0,
+            duration: Duration::ZERO,
+            peer: "127.0.0.1:5000".parse().unwrap(),
+            user_agent: None,
+            referer: None,
+        };
+    }
+
+    #[test]
+    pub fn should_write_on_drop() {
+        let out = Shared::default();
+        let writer = super::LogWriter::new(LogFormat::Common, out.clone());
+        let event = event();
+
+        writer.log(&event);
+        writer.log(&event);
+        drop(writer);
+
+        let lines = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+        assert!(lines.ends_with("\"GET / HTTP/1.1\" 200 -\n"));
+    }
+
+    #[test]
+    pub fn should_drop_when_full() {
+        let (gate, receiver) = mpsc::channel();
+        let writer = super::LogWriter::with_capacity(LogFormat::Common, Gate(receiver), 1);
+
+        for _ in 0..4 {
+            writer.log(&event());
+        }
+
+        // one line is being written and one is queued at most
+        assert!(writer.dropped() >= 2);
+        drop(gate);
+        drop(writer);
+    }
+}
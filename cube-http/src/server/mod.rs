@@ -1,50 +1,994 @@
-use std::{io, net};
+use std::{
+    collections::HashMap,
+    io, net,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub mod router;
 
 mod request;
+use bytes::Bytes;
 use cube_url::Protocol;
 pub use request::*;
 
 mod response;
 pub use response::*;
 
-use crate::RequestMessage;
+mod options;
+pub use options::*;
 
-pub struct Server;
+mod body;
+pub use body::*;
+
+mod stream;
+pub use stream::*;
+
+mod extensions;
+pub use extensions::*;
+
+mod handler;
+pub use handler::*;
+
+pub mod extract;
+
+mod shutdown;
+pub use shutdown::*;
+
+mod pool;
+use pool::Pool;
+
+mod deadline;
+use deadline::Deadline;
+
+pub mod log;
+use log::{AccessEvent, AccessLog};
+
+#[cfg(feature = "sessions")]
+pub mod session;
+
+use crate::{ChunkedWriter, HttpError, Method, RequestMessage, Status};
+use cube_core::error::Error;
+use router::Router;
+
+/// the interim response sent to a client waiting to send the body
+const CONTINUE: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\n";
+
+/// how long to wait for connections to close and free up
+/// file descriptors before accepting again
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// too many open files in the process and in
+/// the system, as numbered on linux and macos
+const EMFILE: i32 = 24;
+const ENFILE: i32 = 23;
+
+pub struct Server {
+    router: Arc<Router>,
+    options: Options,
+    shutdown: Shutdown,
+    access_log: Option<Arc<dyn AccessLog>>,
+}
 
 impl Server {
-    pub fn run<A: net::ToSocketAddrs>(addr: A) -> io::Result<()> {
+    pub fn new(router: Router) -> Self {
+        return Self {
+            router: Arc::new(router),
+            options: Options::new(),
+            shutdown: Shutdown::new(),
+            access_log: None,
+        };
+    }
+
+    /// a handle that gracefully stops the server
+    pub fn shutdown_handle(&self) -> Shutdown {
+        return self.shutdown.clone();
+    }
+
+    /// register application state on the router,
+    /// which must happen before the server runs
+    pub fn state<T: Send + Sync + 'static>(&mut self, value: T) -> &mut Self {
+        Arc::get_mut(&mut self.router)
+            .expect("[cube::http::server] => state must be set before running")
+            .state(value);
+
+        return self;
+    }
+
+    /// report every response written to the access log
+    pub fn access_log<L: AccessLog + 'static>(&mut self, access_log: L) -> &mut Self {
+        self.access_log = Some(Arc::new(access_log));
+        return self;
+    }
+
+    pub fn options(&mut self, options: Options) -> &mut Self {
+        self.options = options;
+        return self;
+    }
+
+    pub fn keep_alive(&mut self, keep_alive: bool) -> &mut Self {
+        self.options.keep_alive = keep_alive;
+        return self;
+    }
+
+    pub fn max_requests(&mut self, max_requests: usize) -> &mut Self {
+        self.options.max_requests = Some(max_requests);
+        return self;
+    }
+
+    pub fn idle_timeout(&mut self, idle_timeout: Duration) -> &mut Self {
+        self.options.idle_timeout = Some(idle_timeout);
+        return self;
+    }
+
+    pub fn head_timeout(&mut self, head_timeout: Duration) -> &mut Self {
+        self.options.head_timeout = Some(head_timeout);
+        return self;
+    }
+
+    pub fn body_timeout(&mut self, body_timeout: Duration) -> &mut Self {
+        self.options.body_timeout = Some(body_timeout);
+        return self;
+    }
+
+    pub fn request_timeout(&mut self, request_timeout: Duration) -> &mut Self {
+        self.options.request_timeout = Some(request_timeout);
+        return self;
+    }
+
+    pub fn max_line_size(&mut self, max_line_size: usize) -> &mut Self {
+        self.options.max_line_size = Some(max_line_size);
+        return self;
+    }
+
+    pub fn max_header_size(&mut self, max_header_size: usize) -> &mut Self {
+        self.options.max_header_size = Some(max_header_size);
+        return self;
+    }
+
+    pub fn max_headers(&mut self, max_headers: usize) -> &mut Self {
+        self.options.max_headers = Some(max_headers);
+        return self;
+    }
+
+    pub fn max_body_size(&mut self, max_body_size: usize) -> &mut Self {
+        self.options.max_body_size = Some(max_body_size);
+        return self;
+    }
+
+    pub fn shutdown_timeout(&mut self, shutdown_timeout: Duration) -> &mut Self {
+        self.options.shutdown_timeout = Some(shutdown_timeout);
+        return self;
+    }
+
+    pub fn signals(&mut self, signals: bool) -> &mut Self {
+        self.options.signals = signals;
+        return self;
+    }
+
+    pub fn workers(&mut self, workers: usize) -> &mut Self {
+        self.options.workers = workers;
+        return self;
+    }
+
+    pub fn queue_size(&mut self, queue_size: usize) -> &mut Self {
+        self.options.queue_size = queue_size;
+        return self;
+    }
+
+    pub fn max_connections(&mut self, max_connections: usize) -> &mut Self {
+        self.options.max_connections = Some(max_connections);
+        return self;
+    }
+
+    pub fn backpressure(&mut self, backpressure: bool) -> &mut Self {
+        self.options.backpressure = backpressure;
+        return self;
+    }
+
+    /// accept connections until shutdown, then wait
+    /// for the open connections to close
+    pub fn run<A: net::ToSocketAddrs>(&self, addr: A) -> std::io::Result<()> {
         let listener = net::TcpListener::bind(addr)?;
 
+        if self.options.signals {
+            self.shutdown.listen()?;
+        }
+
+        Self::wake_on_shutdown(&listener, &self.shutdown)?;
+
+        let router = self.router.clone();
+        let options = self.options;
+        let shutdown = self.shutdown.clone();
+        let access_log = self.access_log.clone();
+        let pool = Pool::new(
+            options.workers,
+            options.queue_size,
+            move |(stream, addr, connection): (net::TcpStream, net::SocketAddr, Connection)| {
+                let _connection = connection;
+                let access_log = access_log.as_deref();
+                Self::on_connect(&router, options, &shutdown, access_log, stream, addr);
+            },
+        );
+
+        let mut result = Ok(());
+
         loop {
-            let (stream, addr) = listener.accept()?;
-            let _ = std::thread::spawn(move || {
-                Self::on_connect(stream, addr);
+            if self.options.backpressure {
+                self.wait_for_room();
+            }
+
+            let accepted = listener.accept();
+
+            if self.shutdown.is_triggered() {
+                break;
+            }
+
+            let (stream, addr) = match accepted {
+                Ok(v) => v,
+                Err(err) => match Self::backoff(&err) {
+                    None => {
+                        result = Err(err);
+                        break;
+                    }
+                    Some(delay) => {
+                        std::thread::sleep(delay);
+                        continue;
+                    }
+                },
+            };
+
+            if self.is_saturated() {
+                let access_log = self.access_log.as_deref();
+                Self::abort(stream, self.unavailable(), access_log, Instant::now(), addr);
+                continue;
+            }
+
+            let job = (stream, addr, self.shutdown.connect());
+            let queued = match self.options.backpressure {
+                true => pool.send(job),
+                false => pool.try_send(job),
+            };
+
+            if let Err((stream, addr, _)) = queued {
+                let access_log = self.access_log.as_deref();
+                Self::abort(stream, self.unavailable(), access_log, Instant::now(), addr);
+            }
+        }
+
+        drop(listener);
+        self.shutdown.drain(self.options.shutdown_timeout);
+        return result;
+    }
+
+    /// unblock `accept` by connecting to the
+    /// listener once the shutdown is triggered
+    fn wake_on_shutdown(listener: &net::TcpListener, shutdown: &Shutdown) -> io::Result<()> {
+        let mut addr = listener.local_addr()?;
+        let shutdown = shutdown.clone();
+
+        if addr.ip().is_unspecified() {
+            addr.set_ip(match addr {
+                net::SocketAddr::V4(_) => net::Ipv4Addr::LOCALHOST.into(),
+                net::SocketAddr::V6(_) => net::Ipv6Addr::LOCALHOST.into(),
             });
         }
+
+        let _ = std::thread::spawn(move || {
+            shutdown.wait();
+            let _ = net::TcpStream::connect(addr);
+        });
+
+        return Ok(());
+    }
+
+    /// how long to wait before accepting again after `err`,
+    /// `None` when the listener itself has failed
+    fn backoff(err: &io::Error) -> Option<Duration> {
+        // out of file descriptors, a connection has to close first
+        if matches!(err.raw_os_error(), Some(EMFILE | ENFILE)) {
+            eprintln!("[cube::http::server] => {}, backing off", err);
+            return Some(ACCEPT_BACKOFF);
+        }
+
+        // the connection went away before it was accepted
+        return match err.kind() {
+            io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock => Some(Duration::ZERO),
+            _ => None,
+        };
+    }
+
+    /// `true` when the max number of connections are open
+    fn is_saturated(&self) -> bool {
+        return self
+            .options
+            .max_connections
+            .is_some_and(|max| self.shutdown.connections() >= max);
+    }
+
+    /// stop accepting connections until one closes
+    fn wait_for_room(&self) {
+        self.shutdown.wait_while(None, |shutdown| {
+            return self.is_saturated() && !shutdown.is_triggered();
+        });
+    }
+
+    fn unavailable(&self) -> Response<Bytes> {
+        return self
+            .router
+            .error(HttpError::new(Status::ServiceUnavailable));
+    }
+
+    /// answer with the response and close the connection
+    fn abort(
+        mut stream: net::TcpStream,
+        response: Response<Bytes>,
+        access_log: Option<&dyn AccessLog>,
+        start: Instant,
+        peer: net::SocketAddr,
+    ) {
+        let response = Self::closing(response);
+        let bytes = match response.to_message().write(&mut stream) {
+            Err(_) => 0,
+            Ok(_) => response.body.as_ref().map(|b| b.len()).unwrap_or(0),
+        };
+
+        Self::log(access_log, None, response.status, bytes, start, peer);
+        let _ = stream.shutdown(net::Shutdown::Both);
+    }
+
+    /// hand the event of a written response to the access log,
+    /// without a message when the request couldn't be read
+    fn log(
+        access_log: Option<&dyn AccessLog>,
+        message: Option<&RequestMessage>,
+        status: Status,
+        bytes: usize,
+        start: Instant,
+        peer: net::SocketAddr,
+    ) {
+        if let Some(access_log) = access_log {
+            access_log.log(&AccessEvent::new(message, status, bytes, start, peer));
+        }
+    }
+
+    /// frame a response that answers no request
+    /// and closes the connection
+    fn closing(mut response: Response<Bytes>) -> Response<Bytes> {
+        let length = response.body.as_ref().map(|b| b.len()).unwrap_or(0);
+        response.stream = None;
+        response.headers(&[
+            ("Content-Length", &length.to_string()),
+            ("Connection", "close"),
+        ]);
+
+        return response;
     }
 
-    fn on_connect(stream: net::TcpStream, _: net::SocketAddr) {
-        let message = match RequestMessage::read(&stream) {
-            Err(err) => {
-                println!("{}", err);
-                return;
+    fn on_connect(
+        router: &Router,
+        options: Options,
+        shutdown: &Shutdown,
+        access_log: Option<&dyn AccessLog>,
+        stream: net::TcpStream,
+        peer: net::SocketAddr,
+    ) {
+        let mut count = 0;
+
+        // kept for the life of the connection, so bytes read
+        // past one message aren't lost to the next
+        let mut reader = io::BufReader::new(Deadline::new(stream));
+
+        while Self::is_ready(&reader, &options, shutdown) {
+            let start = Instant::now();
+            let deadline = Deadline::earliest(None, options.request_timeout);
+            let head = Deadline::earliest(deadline, options.head_timeout);
+            let mut message = match Self::read_head(&mut reader, head, &options) {
+                Err(err) => {
+                    let stream = reader.into_inner().into_inner();
+                    return Self::abort(stream, router.error(err), access_log, start, peer);
+                }
+                Ok(v) => v,
+            };
+
+            count += 1;
+            let mut keep_alive = Self::is_keep_alive(&message, &options, count);
+            let body = Deadline::earliest(deadline, options.body_timeout);
+            let request = match Self::expect(router, &message, &options) {
+                Err(response) => Err(response),
+                Ok(expects) => {
+                    if expects
+                        && io::Write::write_all(reader.get_mut().get_mut(), CONTINUE).is_err()
+                    {
+                        break;
+                    }
+
+                    Self::read_body(&mut message, &mut reader, body, &options)
+                        .and_then(|()| Request::<Bytes>::try_from(&message))
+                        .map_err(|err| router.error(err))
+                }
+            };
+
+            let mut response = match request {
+                Err(response) => {
+                    keep_alive = false;
+                    response
+                }
+                Ok(request) => router.dispatch(request),
+            };
+
+            keep_alive = keep_alive && !shutdown.is_triggered();
+            keep_alive = Self::finalize(&mut response, &message, keep_alive);
+
+            let bytes = match Self::write(reader.get_mut().get_mut(), &mut response) {
+                Err(_) => break,
+                Ok(v) => v,
+            };
+
+            Self::log(
+                access_log,
+                Some(&message),
+                response.status,
+                bytes,
+                start,
+                peer,
+            );
+
+            if !keep_alive {
+                break;
             }
-            Ok(v) => v,
+        }
+
+        let stream = reader.into_inner().into_inner();
+
+        // a connection still queued when the server shut down
+        if count == 0 && shutdown.is_triggered() {
+            let response = router.error(HttpError::new(Status::ServiceUnavailable));
+            return Self::abort(stream, response, access_log, Instant::now(), peer);
+        }
+
+        let _ = stream.shutdown(net::Shutdown::Both);
+    }
+
+    /// wait for the next request on the connection for
+    /// at most the idle timeout, `false` when the client
+    /// closed the connection, never sent anything or the
+    /// server is shutting down
+    fn is_ready(reader: &io::BufReader<Deadline>, options: &Options, shutdown: &Shutdown) -> bool {
+        // the next request was already read with the last one
+        if !reader.buffer().is_empty() {
+            return !shutdown.is_triggered();
+        }
+
+        let stream = reader.get_ref().get_ref();
+        let start = Instant::now();
+        let mut buf = [0; 1];
+        let ready = loop {
+            let mut wait = Duration::from_millis(100);
+
+            if let Some(timeout) = options.idle_timeout {
+                wait = wait.min(timeout.saturating_sub(start.elapsed()));
+            }
+
+            if shutdown.is_triggered() || wait.is_zero() {
+                break false;
+            }
+
+            if stream.set_read_timeout(Some(wait)).is_err() {
+                break false;
+            }
+
+            match stream.peek(&mut buf) {
+                Ok(size) => break size > 0,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => continue,
+                Err(_) => break false,
+            };
         };
 
-        let request = match Request::<String>::try_from(&message) {
-            Err(err) => {
-                println!("{}", err);
-                return;
+        let _ = stream.set_read_timeout(None);
+        return ready;
+    }
+
+    /// a message framed by both `Transfer-Encoding` and `Content-Length`
+    /// may be an attempt at request smuggling, so the connection is closed
+    /// https://www.rfc-editor.org/rfc/rfc9112#section-6.1-15
+    fn is_keep_alive(message: &RequestMessage, options: &Options, count: usize) -> bool {
+        let is_ambiguous = message.header("Transfer-Encoding").is_some()
+            && message.header("Content-Length").is_some();
+
+        return options.keep_alive
+            && message.is_keep_alive()
+            && !is_ambiguous
+            && options.max_requests.is_none_or(|max| count < max);
+    }
+
+    /// read the request line and headers before the deadline
+    fn read_head(
+        reader: &mut io::BufReader<Deadline>,
+        at: Option<Instant>,
+        options: &Options,
+    ) -> Result<RequestMessage, HttpError> {
+        reader.get_mut().set(at)?;
+        let result = RequestMessage::read(&mut *reader, &options.limits());
+        return result.map_err(|err| match reader.get_ref().is_expired() {
+            true => HttpError::new(Status::RequestTimeout),
+            false => err,
+        });
+    }
+
+    /// read the body framed by either the chunked transfer
+    /// coding or `Content-Length` before the deadline
+    fn read_body(
+        message: &mut RequestMessage,
+        reader: &mut io::BufReader<Deadline>,
+        at: Option<Instant>,
+        options: &Options,
+    ) -> Result<(), HttpError> {
+        reader.get_mut().set(at)?;
+        let result = match message.is_chunked()? {
+            true => message.read_chunked(&mut *reader, options.max_body_size, &options.limits()),
+            false => Self::body_length(message, options)
+                .map_err(HttpError::from)
+                .and_then(|length| Ok(message.read_body(&mut *reader, length)?)),
+        };
+
+        return result.map_err(|err| match reader.get_ref().is_expired() {
+            true => HttpError::new(Status::RequestTimeout),
+            false => err,
+        });
+    }
+
+    /// answer `Expect` before the body is read, `true` when the
+    /// client waits for `100 Continue` and an `Err` with the
+    /// response that rejects the request before it sends the body
+    /// https://www.rfc-editor.org/rfc/rfc9110#section-10.1.1
+    #[allow(clippy::result_large_err)]
+    fn expect(
+        router: &Router,
+        message: &RequestMessage,
+        options: &Options,
+    ) -> Result<bool, Response<Bytes>> {
+        let Some(expect) = message.header("Expect") else {
+            return Ok(false);
+        };
+
+        // `HTTP/1.0` clients never wait for an interim response
+        if message.protocol_v == "1.0" {
+            return Ok(false);
+        }
+
+        if !expect.trim().eq_ignore_ascii_case("100-continue") {
+            return Err(router.error(HttpError::new(Status::ExpectationFailed)));
+        }
+
+        let has_body = match message.is_chunked() {
+            Ok(true) => true,
+            _ => {
+                Self::body_length(message, options).map_err(|status| router.error(status.into()))?
+                    > 0
+            }
+        };
+
+        if !has_body {
+            return Ok(false);
+        }
+
+        let request = Request::<Bytes>::try_from(message).map_err(|err| router.error(err))?;
+        router.expect(&request)?;
+        return Ok(true);
+    }
+
+    /// the number of body bytes to read, or the
+    /// status to reject the request with
+    fn body_length(message: &RequestMessage, options: &Options) -> Result<usize, Status> {
+        let length = match message.content_length() {
+            Err(_) => return Err(Status::BadRequest),
+            Ok(v) => v.unwrap_or(0),
+        };
+
+        if options.max_body_size.is_some_and(|max| length > max) {
+            return Err(Status::ContentTooLarge);
+        }
+
+        return Ok(length);
+    }
+
+    /// set the headers that frame the response,
+    /// returning if the connection stays open
+    fn finalize(
+        response: &mut Response<Bytes>,
+        message: &RequestMessage,
+        mut keep_alive: bool,
+    ) -> bool {
+        response.protocol(
+            &Protocol::from(message.protocol.as_str()),
+            &message.protocol_v,
+        );
+
+        let is_head = message.method == Method::Head;
+
+        if response.status == Status::NoContent {
+            // a `204` has no body, nor any framing for one
+            response.body = None;
+            response.stream = None;
+            response.headers.del("Content-Length");
+        } else if response.stream.is_some() && !response.headers.has("Content-Length") {
+            // `HTTP/1.0` has no chunked transfer coding,
+            // so closing the connection ends the body
+            if message.protocol_v == "1.0" {
+                keep_alive = false;
+                response.headers.del("Transfer-Encoding");
+            } else {
+                response.header("Transfer-Encoding", "chunked");
+            }
+        } else if response.stream.is_none() && !(is_head && response.headers.has("Content-Length"))
+        {
+            let length = response.body.as_ref().map(|b| b.len()).unwrap_or(0);
+            response.header("Content-Length", &length.to_string());
+        }
+
+        // a response to `HEAD` is framed like the `GET`
+        // response would be, but never has a body
+        if is_head {
+            response.body = None;
+            response.stream = None;
+        }
+
+        response.header(
+            "Connection",
+            if keep_alive { "keep-alive" } else { "close" },
+        );
+
+        return keep_alive;
+    }
+
+    /// write the response followed by its stream, if any,
+    /// returning the number of body bytes written
+    fn write(stream: &mut net::TcpStream, response: &mut Response<Bytes>) -> Result<usize, Error> {
+        response.to_message().write(stream)?;
+        let mut count = response.body.as_ref().map(|b| b.len()).unwrap_or(0);
+        let Some(mut body) = response.stream.take() else {
+            return Ok(count);
+        };
+
+        if response.headers.has("Transfer-Encoding") {
+            let mut writer = ChunkedWriter::new(&mut *stream);
+            count += io::copy(&mut body, &mut writer)? as usize;
+            writer.finish(&HashMap::new())?;
+        } else {
+            count += io::copy(&mut body, stream)? as usize;
+        }
+
+        return Ok(count);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Server {
+    pub async fn run_async<A: tokio::net::ToSocketAddrs>(&self, addr: A) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+
+        if self.options.signals {
+            self.shutdown.listen_async()?;
+        }
+
+        let mut result = Ok(());
+
+        loop {
+            if self.options.backpressure {
+                self.wait_for_room_async().await;
+            }
+
+            let accepted = tokio::select! {
+                _ = self.shutdown.wait_async() => break,
+                v = listener.accept() => v,
+            };
+
+            let (stream, addr) = match accepted {
+                Ok(v) => v,
+                Err(err) => match Self::backoff(&err) {
+                    None => {
+                        result = Err(err);
+                        break;
+                    }
+                    Some(delay) => {
+                        tokio::select! {
+                            _ = self.shutdown.wait_async() => break,
+                            _ = tokio::time::sleep(delay) => continue,
+                        }
+                    }
+                },
+            };
+
+            if self.is_saturated() {
+                let access_log = self.access_log.as_deref();
+                let response = self.unavailable();
+                Self::abort_async(stream, response, access_log, Instant::now(), addr).await;
+                continue;
+            }
+
+            let router = self.router.clone();
+            let options = self.options;
+            let shutdown = self.shutdown.clone();
+            let access_log = self.access_log.clone();
+            let connection = self.shutdown.connect();
+            tokio::spawn(async move {
+                let _connection = connection;
+                let access_log = access_log.as_deref();
+                Self::on_connect_async(&router, options, &shutdown, access_log, stream, addr).await;
+            });
+        }
+
+        drop(listener);
+        self.shutdown
+            .drain_async(self.options.shutdown_timeout)
+            .await;
+
+        return result;
+    }
+
+    async fn wait_for_room_async(&self) {
+        self.shutdown
+            .wait_while_async(None, |shutdown| {
+                return self.is_saturated() && !shutdown.is_triggered();
+            })
+            .await;
+    }
+
+    async fn abort_async(
+        mut stream: tokio::net::TcpStream,
+        response: Response<Bytes>,
+        access_log: Option<&dyn AccessLog>,
+        start: Instant,
+        peer: net::SocketAddr,
+    ) {
+        use tokio::io::AsyncWriteExt;
+
+        let response = Self::closing(response);
+        let bytes = match response.to_message().write_async(&mut stream).await {
+            Err(_) => 0,
+            Ok(_) => response.body.as_ref().map(|b| b.len()).unwrap_or(0),
+        };
+
+        Self::log(access_log, None, response.status, bytes, start, peer);
+        let _ = stream.shutdown().await;
+    }
+
+    async fn on_connect_async(
+        router: &Router,
+        options: Options,
+        shutdown: &Shutdown,
+        access_log: Option<&dyn AccessLog>,
+        stream: tokio::net::TcpStream,
+        peer: net::SocketAddr,
+    ) {
+        use tokio::io::AsyncWriteExt;
+
+        let mut count = 0;
+
+        // kept for the life of the connection, so bytes read
+        // past one message aren't lost to the next
+        let mut reader = tokio::io::BufReader::new(stream);
+
+        while Self::is_ready_async(&reader, &options, shutdown).await {
+            let start = Instant::now();
+            let deadline = Deadline::earliest(None, options.request_timeout);
+            let head = Deadline::earliest(deadline, options.head_timeout);
+            let mut message = match Self::read_head_async(&mut reader, head, &options).await {
+                Err(err) => {
+                    let stream = reader.into_inner();
+                    let response = router.error(err);
+                    return Self::abort_async(stream, response, access_log, start, peer).await;
+                }
+                Ok(v) => v,
+            };
+
+            count += 1;
+            let mut keep_alive = Self::is_keep_alive(&message, &options, count);
+            let body = Deadline::earliest(deadline, options.body_timeout);
+            let request = match Self::expect(router, &message, &options) {
+                Err(response) => Err(response),
+                Ok(expects) => {
+                    if expects && reader.get_mut().write_all(CONTINUE).await.is_err() {
+                        break;
+                    }
+
+                    Self::read_body_async(&mut message, &mut reader, body, &options)
+                        .await
+                        .and_then(|()| Request::<Bytes>::try_from(&message))
+                        .map_err(|err| router.error(err))
+                }
+            };
+
+            let mut response = match request {
+                Err(response) => {
+                    keep_alive = false;
+                    response
+                }
+                Ok(request) => router.dispatch_async(request).await,
+            };
+
+            keep_alive = keep_alive && !shutdown.is_triggered();
+            keep_alive = Self::finalize(&mut response, &message, keep_alive);
+
+            let bytes = match Self::write_async(reader.get_mut(), &mut response).await {
+                Err(_) => break,
+                Ok(v) => v,
+            };
+
+            Self::log(
+                access_log,
+                Some(&message),
+                response.status,
+                bytes,
+                start,
+                peer,
+            );
+
+            if !keep_alive {
+                break;
             }
+        }
+
+        let mut stream = reader.into_inner();
+
+        // a connection accepted just before the server shut down
+        if count == 0 && shutdown.is_triggered() {
+            let response = router.error(HttpError::new(Status::ServiceUnavailable));
+            return Self::abort_async(stream, response, access_log, Instant::now(), peer).await;
+        }
+
+        let _ = stream.shutdown().await;
+    }
+
+    /// run a future until the deadline, after
+    /// which it fails with `408`
+    async fn timeout<T, F: Future<Output = Result<T, HttpError>>>(
+        at: Option<Instant>,
+        future: F,
+    ) -> Result<T, HttpError> {
+        let Some(at) = at else {
+            return future.await;
+        };
+
+        return match tokio::time::timeout_at(at.into(), future).await {
+            Err(_) => Err(HttpError::new(Status::RequestTimeout)),
             Ok(v) => v,
         };
+    }
+
+    async fn read_head_async(
+        stream: &mut tokio::io::BufReader<tokio::net::TcpStream>,
+        at: Option<Instant>,
+        options: &Options,
+    ) -> Result<RequestMessage, HttpError> {
+        return Self::timeout(at, RequestMessage::read_async(stream, &options.limits())).await;
+    }
+
+    async fn read_body_async(
+        message: &mut RequestMessage,
+        stream: &mut tokio::io::BufReader<tokio::net::TcpStream>,
+        at: Option<Instant>,
+        options: &Options,
+    ) -> Result<(), HttpError> {
+        return Self::timeout(at, async {
+            if message.is_chunked()? {
+                return message
+                    .read_chunked_async(&mut *stream, options.max_body_size, &options.limits())
+                    .await;
+            }
+
+            let length = Self::body_length(message, options)?;
+            message.read_body_async(stream, length).await?;
+            return Ok(());
+        })
+        .await;
+    }
+
+    async fn write_async(
+        stream: &mut tokio::net::TcpStream,
+        response: &mut Response<Bytes>,
+    ) -> Result<usize, Error> {
+        use crate::Chunk;
+        use std::io::Read;
+        use tokio::io::AsyncWriteExt;
+
+        response.to_message().write_async(stream).await?;
+        let mut count = response.body.as_ref().map(|b| b.len()).unwrap_or(0);
+        let Some(mut body) = response.stream.take() else {
+            return Ok(count);
+        };
+
+        // the stream blocks, so it is read on the blocking threads
+        // and handed over until it ends or the client goes away
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<std::io::Result<Bytes>>(4);
+
+        tokio::task::spawn_blocking(move || {
+            let mut buf = vec![0; 8192];
+
+            loop {
+                let chunk = match body.read(&mut buf) {
+                    Ok(0) => return,
+                    Ok(size) => Ok(Bytes::copy_from_slice(&buf[..size])),
+                    Err(err) => Err(err),
+                };
+
+                let failed = chunk.is_err();
+
+                if tx.blocking_send(chunk).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        let chunked = response.headers.has("Transfer-Encoding");
+
+        while let Some(chunk) = rx.recv().await {
+            let chunk = chunk?;
+
+            match chunked {
+                true => stream.write_all(&Chunk::encode(&chunk)).await?,
+                false => stream.write_all(&chunk).await?,
+            };
+
+            count += chunk.len();
+        }
+
+        if chunked {
+            stream.write_all(&Chunk::last(&HashMap::new())).await?;
+        }
+
+        return Ok(count);
+    }
+
+    async fn is_ready_async(
+        reader: &tokio::io::BufReader<tokio::net::TcpStream>,
+        options: &Options,
+        shutdown: &Shutdown,
+    ) -> bool {
+        // the next request was already read with the last one
+        if !reader.buffer().is_empty() {
+            return !shutdown.is_triggered();
+        }
+
+        let stream = reader.get_ref();
+        let mut buf = [0; 1];
+        let peek = async {
+            return match options.idle_timeout {
+                None => stream.peek(&mut buf).await,
+                Some(timeout) => match tokio::time::timeout(timeout, stream.peek(&mut buf)).await {
+                    Err(_) => Ok(0),
+                    Ok(v) => v,
+                },
+            };
+        };
+
+        let size = tokio::select! {
+            _ = shutdown.wait_async() => return false,
+            v = peek => v,
+        };
+
+        return matches!(size, Ok(v) if v > 0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io, time::Duration};
+
+    #[test]
+    pub fn should_back_off() {
+        let err = io::Error::from(io::ErrorKind::ConnectionAborted);
+        assert_eq!(super::Server::backoff(&err), Some(Duration::ZERO));
+
+        let err = io::Error::from_raw_os_error(super::EMFILE);
+        assert_eq!(super::Server::backoff(&err), Some(super::ACCEPT_BACKOFF));
 
-        println!("{}", request);
-        let mut res = Response::<String>::new(stream);
-        let response = res.protocol(&Protocol::from(message.protocol), &message.protocol_v);
-        response.send().unwrap();
+        let err = io::Error::from(io::ErrorKind::InvalidInput);
+        assert_eq!(super::Server::backoff(&err), None);
     }
 }
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::Limits;
+
+/// Server Options
+///
+/// the limits applied to every connection
+/// accepted by the `Server`
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// reuse connections for more than one request
+    /// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Connection
+    pub keep_alive: bool,
+
+    /// the max number of requests served on a single
+    /// connection before it is closed
+    pub max_requests: Option<usize>,
+
+    /// how long a kept alive connection can wait
+    /// for its next request before it is closed
+    pub idle_timeout: Option<Duration>,
+
+    /// how long a client has to send the request line and
+    /// headers once the request starts, answered with `408`
+    pub head_timeout: Option<Duration>,
+
+    /// how long a client has to send the request
+    /// body, answered with `408`
+    pub body_timeout: Option<Duration>,
+
+    /// how long a client has to send a whole request,
+    /// head and body included, answered with `408`
+    pub request_timeout: Option<Duration>,
+
+    /// the max size in bytes of the request
+    /// line, longer lines are answered with `414`
+    pub max_line_size: Option<usize>,
+
+    /// the max size in bytes of the request headers,
+    /// larger headers are answered with `431`
+    pub max_header_size: Option<usize>,
+
+    /// the max number of request headers,
+    /// more are answered with `431`
+    pub max_headers: Option<usize>,
+
+    /// the max size in bytes of a request body,
+    /// larger bodies are answered with `413`
+    pub max_body_size: Option<usize>,
+
+    /// how long to wait for open connections
+    /// to finish their requests on shutdown
+    pub shutdown_timeout: Option<Duration>,
+
+    /// shutdown gracefully on `SIGINT` and `SIGTERM`
+    pub signals: bool,
+
+    /// the number of threads serving connections
+    /// accepted by `Server::run`
+    pub workers: usize,
+
+    /// the max number of accepted connections
+    /// waiting for a free worker
+    pub queue_size: usize,
+
+    /// the max number of open connections,
+    /// including those waiting for a worker
+    pub max_connections: Option<usize>,
+
+    /// when saturated, stop accepting connections until
+    /// there is room instead of answering them with `503`
+    pub backpressure: bool,
+}
+
+impl Options {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            keep_alive: true,
+            max_requests: Some(1000),
+            idle_timeout: Some(Duration::from_secs(5)),
+            head_timeout: Some(Duration::from_secs(10)),
+            body_timeout: Some(Duration::from_secs(30)),
+            request_timeout: Some(Duration::from_secs(60)),
+            max_line_size: Some(8 * 1024),
+            max_header_size: Some(32 * 1024),
+            max_headers: Some(100),
+            max_body_size: Some(1024 * 1024),
+            shutdown_timeout: Some(Duration::from_secs(30)),
+            signals: true,
+            workers: 64,
+            queue_size: 256,
+            max_connections: Some(1024),
+            backpressure: false,
+        };
+    }
+
+    /// the limits applied when reading a request head
+    pub fn limits(&self) -> Limits {
+        return Limits {
+            max_line_size: self.max_line_size,
+            max_header_size: self.max_header_size,
+            max_headers: self.max_headers,
+        };
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, mpsc},
+    thread,
+};
+
+/// Worker Pool
+///
+/// a fixed number of threads that handle jobs
+/// sent through a queue of bounded size, a job
+/// that panics doesn't take its thread with it
+pub(crate) struct Pool<T: Send + 'static> {
+    sender: mpsc::SyncSender<T>,
+}
+
+impl<T: Send + 'static> Pool<T> {
+    pub fn new<F: Fn(T) + Send + Sync + 'static>(
+        size: usize,
+        queue_size: usize,
+        handler: F,
+    ) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<T>(queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let handler = Arc::new(handler);
+
+        for i in 0..size.max(1) {
+            let receiver = receiver.clone();
+            let handler = handler.clone();
+            let _ = thread::Builder::new()
+                .name(format!("cube-worker-{}", i))
+                .spawn(move || {
+                    loop {
+                        let job = match receiver.lock() {
+                            Err(_) => break,
+                            Ok(v) => v.recv(),
+                        };
+
+                        match job {
+                            Err(_) => break,
+                            Ok(job) => {
+                                let _ = panic::catch_unwind(AssertUnwindSafe(|| handler(job)));
+                            }
+                        };
+                    }
+                });
+        }
+
+        return Self { sender };
+    }
+
+    /// queue a job, blocking while the queue is full
+    pub fn send(&self, job: T) -> Result<(), T> {
+        return self.sender.send(job).map_err(|err| err.0);
+    }
+
+    /// queue a job, giving it back when the queue is full
+    pub fn try_send(&self, job: T) -> Result<(), T> {
+        return match self.sender.try_send(job) {
+            Ok(()) => Ok(()),
+            Err(mpsc::TrySendError::Full(job)) => Err(job),
+            Err(mpsc::TrySendError::Disconnected(job)) => Err(job),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Mutex, mpsc};
+
+    #[test]
+    pub fn should_run_jobs() {
+        let (sender, receiver) = mpsc::channel::<usize>();
+        let pool = super::Pool::new(2, 4, move |job: usize| {
+            sender.send(job * 2).unwrap();
+        });
+
+        for i in 0..4 {
+            pool.send(i).unwrap();
+        }
+
+        let mut results = receiver.iter().take(4).collect::<Vec<_>>();
+        results.sort();
+        assert_eq!(results, vec![0, 2, 4, 6]);
+    }
+
+    #[test]
+    pub fn should_survive_panics() {
+        let (sender, receiver) = mpsc::channel::<usize>();
+        let pool = super::Pool::new(1, 4, move |job: usize| {
+            if job == 0 {
+                panic!("oops");
+            }
+
+            sender.send(job).unwrap();
+        });
+
+        pool.send(0).unwrap();
+        pool.send(1).unwrap();
+        assert_eq!(receiver.recv().unwrap(), 1);
+    }
+
+    #[test]
+    pub fn should_give_back_when_full() {
+        let (started, on_start) = mpsc::channel::<()>();
+        let (release, on_release) = mpsc::channel::<()>();
+        let started = Mutex::new(started);
+        let on_release = Mutex::new(on_release);
+        let pool = super::Pool::new(1, 1, move |_: usize| {
+            started.lock().unwrap().send(()).unwrap();
+            let _ = on_release.lock().unwrap().recv();
+        });
+
+        pool.send(1).unwrap();
+        on_start.recv().unwrap();
+
+        assert_eq!(pool.try_send(2), Ok(()));
+        assert_eq!(pool.try_send(3), Err(3));
+
+        drop(release);
+    }
+}
@@ -1,7 +1,10 @@
-use cube_core::error::Error;
-use cube_url::Url;
+use bytes::Bytes;
+use cube_url::{Params, Url};
 
-use crate::{Headers, Method, RequestMessage};
+use crate::{
+    ContentType, Cookie, Headers, HttpError, Method, RequestMessage, Status,
+    server::{Extensions, FromBody},
+};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -10,24 +13,144 @@ pub struct Request<T> {
     pub url: Url,
     pub headers: Headers,
     pub body: Option<T>,
+
+    /// the fields sent after a chunked body, kept apart
+    /// from `headers` so they can't pose as one of them
+    /// https://www.rfc-editor.org/rfc/rfc9110#section-6.5.1
+    pub trailers: Headers,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub extensions: Extensions,
 }
 
-impl<T> TryFrom<&RequestMessage> for Request<T> {
-    type Error = Error;
+impl<T> Request<T> {
+    /// the params captured from the path
+    /// by the matched route
+    pub fn params(&self) -> &Params {
+        return self.url.params();
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        return self.url.params().get(name);
+    }
+
+    /// the application state of type `S`
+    pub fn state<S: Send + Sync + 'static>(&self) -> Option<&S> {
+        return self.extensions.get::<S>();
+    }
+
+    /// a copy of the request without its body
+    pub fn head(&self) -> Request<()> {
+        return Request {
+            method: self.method,
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body: None,
+            trailers: self.trailers.clone(),
+            extensions: self.extensions.clone(),
+        };
+    }
+
+    /// the `Content-Type` of the body, `None` when
+    /// it is missing or not a known `ContentType`
+    pub fn content_type(&self) -> Option<ContentType> {
+        let value = self.headers.get("Content-Type")?.to_string();
+        return ContentType::try_from(value.as_str()).ok();
+    }
+
+    /// the cookies sent in the `Cookie` header
+    pub fn cookies(&self) -> Vec<Cookie> {
+        return match self.headers.get("Cookie") {
+            None => vec![],
+            Some(v) => Cookie::parse(&v.to_string()),
+        };
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<Cookie> {
+        return self.cookies().into_iter().find(|c| c.name == name);
+    }
+}
+
+impl Request<Bytes> {
+    /// decode the raw body into another body type, which
+    /// checks its `Content-Type`, such as `Payload` picking
+    /// the codec for it from `Codecs`
+    pub fn decode<T: FromBody>(self) -> Result<Request<T>, HttpError> {
+        let content_type = self.content_type();
+        let body = match self.body {
+            None => None,
+            Some(v) => Some(T::from_body(content_type, v)?),
+        };
+
+        return Ok(Request {
+            method: self.method,
+            url: self.url,
+            headers: self.headers,
+            body,
+            trailers: self.trailers,
+            extensions: self.extensions,
+        });
+    }
+}
+
+impl<T: FromBody> TryFrom<&RequestMessage> for Request<T> {
+    type Error = HttpError;
 
     fn try_from(request: &RequestMessage) -> Result<Self, Self::Error> {
-        return Ok(Self {
+        let request = Request::<Bytes> {
             method: request.method,
             headers: Headers::from(&request.headers),
-            url: Url::parse(&format!(
-                "{}://{}{}",
-                request.protocol,
-                &request.headers.get("Host").unwrap(),
-                &request.path,
-            ))?,
-            body: None,
-        });
+            url: target(request)?,
+            body: match request.body.is_empty() {
+                true => None,
+                false => Some(request.body.clone()),
+            },
+            trailers: Headers::from(&request.trailers),
+            extensions: Extensions::new(),
+        };
+
+        return request.decode();
+    }
+}
+
+/// the url of the request target, an absolute target takes
+/// precedence over `Host`, which is required from `HTTP/1.1`
+/// clients and must be a single valid authority
+/// https://www.rfc-editor.org/rfc/rfc9112#section-3.2
+fn target(message: &RequestMessage) -> Result<Url, HttpError> {
+    let path = message.path.to_lowercase();
+
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return Ok(Url::parse(&message.path)?);
+    }
+
+    // `OPTIONS *` asks about the server rather than a resource
+    let is_asterisk = message.path == "*" && message.method == Method::Options;
+
+    if !message.path.starts_with('/') && !is_asterisk {
+        return Err(HttpError::new(Status::BadRequest).message("invalid request target"));
+    }
+
+    let host = match message.header("Host") {
+        Some(v) => v,
+        None if message.protocol_v == "1.0" => "localhost",
+        None => return Err(HttpError::new(Status::BadRequest).message("missing host")),
+    };
+
+    if host.is_empty() || host.contains(|c: char| c.is_whitespace() || "/?#@,".contains(c)) {
+        return Err(HttpError::new(Status::BadRequest).message("invalid host"));
+    }
+
+    if is_asterisk {
+        let mut url = Url::parse(&format!("{}://{}/", message.protocol, host))?;
+        *url.path_mut() = message.path.clone();
+        return Ok(url);
     }
+
+    return Ok(Url::parse(&format!(
+        "{}://{}{}",
+        message.protocol, host, message.path
+    ))?);
 }
 
 #[cfg(feature = "serde")]
@@ -36,3 +159,93 @@ impl<T: serde::Serialize> std::fmt::Display for Request<T> {
         return write!(f, "{}", serde_json::to_string_pretty(self).unwrap());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Headers, Method, RequestMessage, Status,
+        server::{Extensions, router::Router},
+    };
+
+    fn message(version: &str, path: &str, host: Option<&str>) -> RequestMessage {
+        let mut headers = HashMap::new();
+
+        if let Some(v) = host {
+            headers.insert(String::from("host"), v.to_string());
+        }
+
+        return RequestMessage {
+            method: Method::Get,
+            path: path.to_string(),
+            protocol: String::from("http"),
+            protocol_v: version.to_string(),
+            headers,
+            body: Bytes::new(),
+            trailers: HashMap::new(),
+        };
+    }
+
+    #[test]
+    pub fn should_require_host() {
+        let req =
+            super::Request::<Bytes>::try_from(&message("1.1", "/users", Some("localhost:3000")));
+        assert_eq!(req.unwrap().url.host(), "localhost:3000");
+
+        let req = super::Request::<Bytes>::try_from(&message("1.0", "/users", None));
+        assert_eq!(req.unwrap().url.host(), "localhost");
+
+        let req =
+            super::Request::<Bytes>::try_from(&message("1.1", "http://example.com/users", None));
+        assert_eq!(req.unwrap().url.host(), "example.com");
+
+        for (path, host) in [
+            ("/users", None),
+            ("/users", Some("a, b")),
+            ("users", Some("a")),
+        ] {
+            let err = super::Request::<Bytes>::try_from(&message("1.1", path, host)).unwrap_err();
+            assert_eq!(err.status, Status::BadRequest);
+        }
+    }
+
+    #[test]
+    pub fn should_keep_trailers_apart() {
+        let mut message = message("1.1", "/users", Some("localhost"));
+        message.body = Bytes::from_static(b"data");
+        message
+            .trailers
+            .insert(String::from("Authorization"), String::from("Bearer token"));
+
+        let req = super::Request::<Bytes>::try_from(&message).unwrap();
+        assert!(!req.headers.has("Authorization"));
+        assert_eq!(
+            req.trailers.get("Authorization").map(|v| v.to_string()),
+            Some(String::from("Bearer token"))
+        );
+    }
+
+    #[test]
+    pub fn should_get_params() {
+        let mut router = Router::new();
+        router.get("/users/{id}", |req: super::Request<Bytes>| {
+            return req.param("id").unwrap_or_default().to_string();
+        });
+
+        let res = router.dispatch(super::Request {
+            method: Method::Get,
+            url: Url::parse("http://localhost/users/42").unwrap(),
+            headers: Headers::new(),
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        });
+
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(res.body.as_deref(), Some(b"42".as_slice()));
+    }
+}
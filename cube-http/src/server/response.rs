@@ -1,7 +1,13 @@
+use std::io;
+
+use bytes::Bytes;
 use cube_core::error::Error;
 use cube_url::Protocol;
 
-use crate::{Header, Headers, ResponseMessage, Status};
+use crate::{
+    Cookie, Header, Headers, HttpError, ResponseMessage, Status,
+    server::{IntoBody, Stream},
+};
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -12,19 +18,26 @@ pub struct Response<T> {
     pub headers: Headers,
     pub body: Option<T>,
 
-    #[serde(skip)]
-    stream: std::net::TcpStream,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub stream: Option<Stream>,
+
+    /// the error this response was made from,
+    /// which the router can map to another response
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub error: Option<HttpError>,
 }
 
 impl<T> Response<T> {
-    pub fn new(stream: std::net::TcpStream) -> Self {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
         return Self {
             protocol: Protocol::Http,
             protocol_v: String::from("1.1"),
             status: Status::Ok,
             headers: Headers::new(),
             body: None,
-            stream,
+            stream: None,
+            error: None,
         };
     }
 
@@ -52,36 +65,334 @@ impl<T> Response<T> {
         return self;
     }
 
-    pub fn send(&mut self) -> Result<usize, Error> {
-        let count = self.to_message().write(&mut self.stream)?;
-        self.stream.shutdown(std::net::Shutdown::Both)?;
-        return Ok(count);
+    /// add a request header name to `Vary`,
+    /// unless it is already listed
+    pub fn vary(&mut self, name: &str) -> &mut Self {
+        let value = match self.headers.get("Vary").map(Header::to_string) {
+            None => name.to_string(),
+            Some(v)
+                if v.trim() == "*" || v.split(',').any(|v| v.trim().eq_ignore_ascii_case(name)) =>
+            {
+                return self;
+            }
+            Some(v) => format!("{}, {}", v, name),
+        };
+
+        self.headers.set("Vary", &Header::Raw(value));
+        return self;
+    }
+
+    /// the cookies set with `Set-Cookie`
+    pub fn cookies(&self) -> Vec<Cookie> {
+        return match self.headers.get("Set-Cookie") {
+            None => vec![],
+            Some(v) => v
+                .values()
+                .into_iter()
+                .filter_map(|v| Cookie::try_from(v).ok())
+                .collect(),
+        };
+    }
+
+    /// add a `Set-Cookie` header like `try_cookie`, for cookies
+    /// known to be valid, panics when `Cookie::validate` fails
+    pub fn cookie(&mut self, cookie: &Cookie) -> &mut Self {
+        if let Err(err) = self.try_cookie(cookie) {
+            panic!("{}", err);
+        }
+
+        return self;
+    }
+
+    /// add a `Set-Cookie` header, replacing any cookie set with
+    /// the same name, domain and path, or with the same name when
+    /// it is a removal, failing without adding it when
+    /// `Cookie::validate` fails, such as for a value taken
+    /// from the request
+    pub fn try_cookie(&mut self, cookie: &Cookie) -> Result<&mut Self, Error> {
+        cookie.validate()?;
+
+        let is_removal = cookie.is_removal();
+        let mut values = match self.headers.get("Set-Cookie") {
+            None => vec![],
+            Some(v) => v
+                .values()
+                .into_iter()
+                .filter(|v| {
+                    return !Cookie::try_from(*v).is_ok_and(|v| {
+                        v.name == cookie.name
+                            && (is_removal || (v.domain == cookie.domain && v.path == cookie.path))
+                    });
+                })
+                .map(str::to_string)
+                .collect(),
+        };
+
+        values.push(cookie.to_string());
+        self.headers.set("Set-Cookie", &Header::List(values));
+        return Ok(self);
+    }
+
+    /// tell the client to remove the cookie `name` set on the
+    /// path `/`, use `Cookie::removal` for other paths or domains
+    pub fn remove_cookie(&mut self, name: &str) -> &mut Self {
+        return self.cookie(Cookie::removal(name).path("/"));
+    }
+
+    pub fn body(&mut self, body: T) -> &mut Self {
+        self.body = Some(body);
+        return self;
+    }
+
+    /// write the body from a reader after the headers, using
+    /// the chunked transfer coding unless a `Content-Length`
+    /// is set
+    pub fn stream<R: io::Read + Send + 'static>(&mut self, reader: R) -> &mut Self {
+        self.stream = Some(Stream::new(reader));
+        return self;
     }
 
+    /// convert the body into another type,
+    /// keeping the status line and headers
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Response<U> {
+        return Response {
+            protocol: self.protocol,
+            protocol_v: self.protocol_v,
+            status: self.status,
+            headers: self.headers,
+            body: self.body.map(f),
+            stream: self.stream,
+            error: self.error,
+        };
+    }
+}
+
+impl<T: IntoBody> Response<T> {
+    /// encode the body into raw bytes, setting `Content-Type`
+    /// from the body when the handler hasn't set one
+    pub fn encode(mut self) -> Result<Response<Bytes>, Error> {
+        if let Some(body) = &self.body
+            && !self.headers.has("Content-Type")
+        {
+            self.header("Content-Type", &body.content_type().to_string());
+        }
+
+        return Ok(Response {
+            protocol: self.protocol,
+            protocol_v: self.protocol_v,
+            status: self.status,
+            headers: self.headers,
+            body: self.body.map(IntoBody::into_body).transpose()?,
+            stream: self.stream,
+            error: self.error,
+        });
+    }
+}
+
+impl Response<Bytes> {
     pub fn to_message(&self) -> ResponseMessage {
         return ResponseMessage {
-            protocol: self.protocol.to_string(),
+            protocol: self.protocol.to_string().to_uppercase(),
             protocol_v: self.protocol_v.clone(),
             status: self.status,
             headers: self.headers.clone().into(),
+            body: self.body.clone().unwrap_or_default(),
         };
     }
 }
 
-impl<T> Into<ResponseMessage> for Response<T> {
+#[allow(clippy::from_over_into)]
+impl Into<ResponseMessage> for Response<Bytes> {
     fn into(self) -> ResponseMessage {
         return ResponseMessage {
-            protocol: self.protocol.to_string(),
+            protocol: self.protocol.to_string().to_uppercase(),
             protocol_v: self.protocol_v.clone(),
             status: self.status,
             headers: self.headers.into(),
+            body: self.body.unwrap_or_default(),
+        };
+    }
+}
+
+/// convert the return value of a
+/// handler into a `Response`
+pub trait IntoResponse {
+    fn into_response(self) -> Response<Bytes>;
+}
+
+impl<T: IntoBody> IntoResponse for Response<T> {
+    fn into_response(self) -> Response<Bytes> {
+        return match self.encode() {
+            Ok(v) => v,
+            Err(err) => HttpError::new(Status::InternalServerError)
+                .message(&err.to_string())
+                .into_response(),
+        };
+    }
+}
+
+impl IntoResponse for () {
+    fn into_response(self) -> Response<Bytes> {
+        return Response::new();
+    }
+}
+
+impl IntoResponse for Status {
+    fn into_response(self) -> Response<Bytes> {
+        let mut res = Response::<Bytes>::new();
+        res.status(self);
+        return res;
+    }
+}
+
+/// the status with its message as a text body,
+/// keeping the error for the router to map
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response<Bytes> {
+        let mut res = Response::<String>::new();
+        res.status(self.status);
+
+        if let Some(message) = &self.message {
+            res.body(message.clone());
+        }
+
+        let mut res = res.into_response();
+        res.error = Some(self);
+        return res;
+    }
+}
+
+impl<T: IntoResponse, E: IntoResponse> IntoResponse for Result<T, E> {
+    fn into_response(self) -> Response<Bytes> {
+        return match self {
+            Ok(v) => v.into_response(),
+            Err(err) => err.into_response(),
         };
     }
 }
 
+impl<T: IntoBody> IntoResponse for (Status, T) {
+    fn into_response(self) -> Response<Bytes> {
+        let mut res = Response::<T>::new();
+        res.status(self.0).body(self.1);
+        return res.into_response();
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+impl IntoResponse for &'static str {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+impl IntoResponse for Bytes {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+impl IntoResponse for Vec<u8> {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoResponse for crate::server::Json<T> {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoResponse for crate::server::Form<T> {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> IntoResponse for crate::server::Payload<T> {
+    fn into_response(self) -> Response<Bytes> {
+        return (Status::Ok, self).into_response();
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<T: serde::Serialize> std::fmt::Display for Response<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return write!(f, "{}", serde_json::to_string_pretty(self).unwrap());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Cookie, Header};
+
+    #[test]
+    pub fn should_encode() {
+        let mut res = super::Response::<String>::new();
+        res.body(String::from("hello world"));
+
+        let res = res.encode().unwrap();
+        assert_eq!(res.body.as_deref(), Some(b"hello world".as_slice()));
+        assert_eq!(
+            res.headers.get("Content-Type").map(Header::to_string),
+            Some(String::from("text/plain; charset=utf-8"))
+        );
+
+        let mut res = super::Response::<String>::new();
+        res.header("Content-Type", "text/csv")
+            .body(String::from("a,b"));
+
+        let res = res.encode().unwrap();
+        assert_eq!(
+            res.headers.get("Content-Type").map(Header::to_string),
+            Some(String::from("text/csv"))
+        );
+    }
+
+    #[test]
+    pub fn should_set_cookies() {
+        let mut res = super::Response::<String>::new();
+        res.cookie(Cookie::new("id", "abc").path("/").http_only(true))
+            .cookie(&Cookie::new("theme", "dark"))
+            .cookie(Cookie::new("id", "def").path("/").http_only(true))
+            .remove_cookie("theme");
+
+        let message = res.encode().unwrap().to_message();
+        let lines = message
+            .headers
+            .iter()
+            .filter(|(k, _)| k == "Set-Cookie")
+            .map(|(_, v)| v.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "id=def; Path=/; HttpOnly");
+        assert!(lines[1].starts_with("theme=; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=0"));
+    }
+
+    #[test]
+    pub fn should_refuse_invalid_cookies() {
+        let mut res = super::Response::<String>::new();
+        assert!(
+            res.try_cookie(&Cookie::new("id", "abc\r\nX-Injected: 1"))
+                .is_err()
+        );
+        assert!(
+            res.try_cookie(&Cookie::new("id", "abc;Path=/admin"))
+                .is_err()
+        );
+        assert!(res.headers.get("Set-Cookie").is_none());
+
+        assert!(res.try_cookie(&Cookie::new("id", "abc")).is_ok());
+        assert_eq!(res.cookies().len(), 1);
+    }
+}
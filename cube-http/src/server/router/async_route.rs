@@ -0,0 +1,109 @@
+use std::{
+    marker::PhantomData,
+    panic,
+    sync::{Arc, OnceLock},
+};
+
+use bytes::Bytes;
+use cube_url::template::Template;
+
+use crate::{
+    Method,
+    server::{
+        AsyncHandler, Request, Response,
+        router::{BoxFuture, Endpoint, Layer, Layers},
+    },
+};
+
+/// built once for the threads that run async
+/// routes outside of a runtime of their own
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    return RUNTIME.get_or_init(|| {
+        return tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("[cube::http::router] => failed to build runtime");
+    });
+}
+
+/// a `Route` whose handler returns a future,
+/// used by `Server::run_async`
+pub struct AsyncRoute<Args, H: AsyncHandler<Args>> {
+    __phantom_args__: PhantomData<fn() -> Args>,
+    method: Option<Method>,
+    path: Template,
+    layers: Layers,
+    handler: H,
+}
+
+impl<Args, H: AsyncHandler<Args>> AsyncRoute<Args, H> {
+    pub fn new(path: Template, handler: H) -> Self {
+        return Self {
+            __phantom_args__: PhantomData,
+            method: None,
+            path,
+            layers: Layers::new(),
+            handler,
+        };
+    }
+
+    pub fn method(&mut self, method: Method) -> &mut Self {
+        self.method = Some(method);
+        return self;
+    }
+
+    /// add a layer that only wraps this route
+    pub fn layer<L: Layer + 'static>(&mut self, layer: L) -> &mut Self {
+        self.layers.push(layer);
+        return self;
+    }
+
+    pub async fn invoke(&self, req: Request<Bytes>) -> Response<Bytes> {
+        return self.handler.call(req).await;
+    }
+}
+
+impl<Args: 'static, H: AsyncHandler<Args>> Endpoint for AsyncRoute<Args, H> {
+    fn method(&self) -> Option<Method> {
+        return self.method;
+    }
+
+    fn path(&self) -> &Template {
+        return &self.path;
+    }
+
+    fn layers(&self) -> &Layers {
+        return &self.layers;
+    }
+
+    /// drive the handler to completion on the thread, such as a
+    /// worker of the threaded `Server`, inside the runtime the
+    /// thread already runs, or on a scoped thread when that
+    /// runtime has a single thread that can't be blocked
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes> {
+        use tokio::runtime::{Handle, RuntimeFlavor};
+
+        let future = self.handler.call(req);
+        let Ok(handle) = Handle::try_current() else {
+            return runtime().block_on(future);
+        };
+
+        if handle.runtime_flavor() == RuntimeFlavor::MultiThread {
+            return tokio::task::block_in_place(|| handle.block_on(future));
+        }
+
+        return std::thread::scope(|scope| {
+            return scope
+                .spawn(move || runtime().block_on(future))
+                .join()
+                .unwrap_or_else(|err| panic::resume_unwind(err));
+        });
+    }
+
+    fn call_async(self: Arc<Self>, req: Request<Bytes>) -> BoxFuture<'static, Response<Bytes>> {
+        return Box::pin(async move { self.handler.call(req).await });
+    }
+}
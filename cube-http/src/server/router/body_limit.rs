@@ -0,0 +1,52 @@
+use bytes::Bytes;
+
+use crate::{
+    HttpError, Status,
+    server::{IntoResponse, Request, Response, router::Layer},
+};
+
+/// Body Limit
+///
+/// answers requests with a body larger than `max` bytes with
+/// `413`, before the body is sent when the client waits for
+/// `100 Continue`
+///
+/// Example
+/// -------
+/// `route.layer(BodyLimit::new(1024))`
+pub struct BodyLimit {
+    max: usize,
+}
+
+impl BodyLimit {
+    pub fn new(max: usize) -> Self {
+        return Self { max };
+    }
+
+    fn too_large() -> Response<Bytes> {
+        return HttpError::new(Status::ContentTooLarge).into_response();
+    }
+}
+
+impl Layer for BodyLimit {
+    fn before(&self, req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        if req.body.as_ref().is_some_and(|b| b.len() > self.max) {
+            return Err(Self::too_large());
+        }
+
+        return Ok(());
+    }
+
+    fn expect(&self, req: &Request<()>) -> Result<(), Response<Bytes>> {
+        let length = req
+            .headers
+            .get("Content-Length")
+            .and_then(|v| v.to_string().trim().parse::<usize>().ok());
+
+        if length.is_some_and(|v| v > self.max) {
+            return Err(Self::too_large());
+        }
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,326 @@
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use flate2::{
+    Compression, read,
+    write::{GzEncoder, ZlibEncoder},
+};
+
+use crate::{
+    ContentType, Status,
+    server::{Request, Response, Stream, router::Layer},
+};
+
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Content-Encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        };
+    }
+
+    /// pick the encoding with the highest q-value in an `Accept-Encoding`
+    /// header, preferring `Gzip` on ties, `None` when neither is accepted
+    pub fn negotiate(accept: &str) -> Option<Self> {
+        let mut gzip = None::<f32>;
+        let mut deflate = None::<f32>;
+        let mut any = None::<f32>;
+
+        for item in accept.split(',') {
+            let mut parts = item.split(';');
+            let name = parts.next().unwrap_or("").trim().to_lowercase();
+            let q = parts
+                .filter_map(|v| v.trim().strip_prefix("q="))
+                .find_map(|v| v.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            match name.as_str() {
+                "gzip" | "x-gzip" => gzip = Some(q),
+                "deflate" => deflate = Some(q),
+                "*" => any = Some(q),
+                _ => {}
+            };
+        }
+
+        let gzip = gzip.or(any).unwrap_or(0.0);
+        let deflate = deflate.or(any).unwrap_or(0.0);
+
+        if gzip <= 0.0 && deflate <= 0.0 {
+            return None;
+        }
+
+        return match deflate > gzip {
+            true => Some(Self::Deflate),
+            false => Some(Self::Gzip),
+        };
+    }
+
+    pub fn encode(&self, data: &[u8], level: u32) -> std::io::Result<Vec<u8>> {
+        let level = Compression::new(level);
+
+        return match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(vec![], level);
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Deflate => {
+                let mut encoder = ZlibEncoder::new(vec![], level);
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        };
+    }
+
+    /// a stream that encodes `reader` as it is read
+    pub fn encode_stream<R: io::Read + Send + 'static>(&self, reader: R, level: u32) -> Stream {
+        let level = Compression::new(level);
+
+        return match self {
+            Self::Gzip => Stream::new(read::GzEncoder::new(reader, level)),
+            Self::Deflate => Stream::new(read::ZlibEncoder::new(reader, level)),
+        };
+    }
+}
+
+/// Compress
+///
+/// compresses response bodies of at least `min_size` bytes with
+/// the encoding the client prefers in `Accept-Encoding`, skipping
+/// bodies that are already encoded and content types that are
+/// unknown or already compressed such as `Png`, streams are
+/// compressed as they are written and sent chunked
+///
+/// Example
+/// -------
+/// `router.layer(Compress::new())`
+pub struct Compress {
+    min_size: usize,
+    level: u32,
+}
+
+impl Compress {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            min_size: 1024,
+            level: 6,
+        };
+    }
+
+    /// the smallest body in bytes worth compressing
+    pub fn min_size(&mut self, size: usize) -> &mut Self {
+        self.min_size = size;
+        return self;
+    }
+
+    /// the compression level, from `0` to `9`
+    pub fn level(&mut self, level: u32) -> &mut Self {
+        self.level = level.min(9);
+        return self;
+    }
+
+    fn is_eligible(res: &Response<Bytes>) -> bool {
+        if res.headers.has("Content-Encoding")
+            || matches!(res.status, Status::NoContent | Status::NotModified)
+        {
+            return false;
+        }
+
+        return match res
+            .headers
+            .get("Content-Type")
+            .map(|v| ContentType::try_from(v.to_string().as_str()))
+        {
+            Some(Ok(v)) => !v.is_compressed() && v != ContentType::OctetStream,
+            _ => false,
+        };
+    }
+}
+
+impl Layer for Compress {
+    fn after(&self, req: &Request<()>, res: &mut Response<Bytes>) {
+        if !Self::is_eligible(res) {
+            return;
+        }
+
+        res.vary("Accept-Encoding");
+
+        // the length of a stream is only known from `Content-Length`
+        let size = match &res.stream {
+            Some(_) => res
+                .headers
+                .get("Content-Length")
+                .and_then(|v| v.to_string().parse::<usize>().ok())
+                .unwrap_or(usize::MAX),
+            None => res.body.as_ref().map(|b| b.len()).unwrap_or(0),
+        };
+
+        if size < self.min_size {
+            return;
+        }
+
+        let Some(encoding) = req
+            .headers
+            .get("Accept-Encoding")
+            .and_then(|v| Encoding::negotiate(&v.to_string()))
+        else {
+            return;
+        };
+
+        // the compressed length isn't known until the stream
+        // ends, so it is sent with the chunked transfer coding
+        if let Some(stream) = res.stream.take() {
+            res.headers.del("Content-Length");
+            res.header("Content-Encoding", encoding.as_str());
+            res.stream = Some(encoding.encode_stream(stream, self.level));
+            return;
+        }
+
+        let Some(body) = &res.body else {
+            return;
+        };
+
+        let Ok(data) = encoding.encode(body, self.level) else {
+            return;
+        };
+
+        if data.len() >= body.len() {
+            return;
+        }
+
+        if res.headers.has("Content-Length") {
+            res.header("Content-Length", &data.len().to_string());
+        }
+
+        res.header("Content-Encoding", encoding.as_str())
+            .body(Bytes::from(data));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+
+    use bytes::Bytes;
+    use cube_url::Url;
+    use flate2::read::GzDecoder;
+
+    use crate::{
+        Header, Headers, Method, Status,
+        server::{Extensions, Request, Response, router::Router},
+    };
+
+    use super::{Compress, Encoding};
+
+    fn get(router: &Router, path: &str, accept: &str) -> Response<Bytes> {
+        let mut headers = Headers::new();
+        headers.set("Accept-Encoding", &Header::from(accept));
+
+        return router.dispatch(Request {
+            method: Method::Get,
+            url: Url::parse(&format!("http://localhost{}", path)).unwrap(),
+            headers,
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        });
+    }
+
+    #[test]
+    pub fn should_negotiate() {
+        assert_eq!(
+            Encoding::negotiate("gzip, deflate, br, zstd"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            Encoding::negotiate("gzip;q=0.5, deflate"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(Encoding::negotiate("*;q=0.1"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("gzip;q=0, *"), Some(Encoding::Deflate));
+        assert_eq!(Encoding::negotiate("br, identity"), None);
+        assert_eq!(Encoding::negotiate("gzip;q=0"), None);
+    }
+
+    #[test]
+    pub fn should_compress() {
+        let text = "hello world ".repeat(200);
+        let mut router = Router::new();
+        let body = text.clone();
+
+        router
+            .layer(Compress::new())
+            .get("/text", move || body.clone())
+            .get("/small", || "hello world")
+            .get("/image", || {
+                let mut res = Response::<Vec<u8>>::new();
+                res.header("Content-Type", "image/png").body(vec![0; 4096]);
+                return res;
+            });
+
+        let res = get(&router, "/text", "deflate;q=0.5, gzip");
+        assert_eq!(
+            res.headers.get("Content-Encoding").unwrap().to_string(),
+            "gzip"
+        );
+        assert_eq!(
+            res.headers.get("Vary").unwrap().to_string(),
+            "Accept-Encoding"
+        );
+
+        let mut decoded = String::new();
+        GzDecoder::new(res.body.as_deref().unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+
+        let res = get(&router, "/small", "gzip");
+        assert_eq!(res.status, Status::Ok);
+        assert!(!res.headers.has("Content-Encoding"));
+
+        let res = get(&router, "/image", "gzip");
+        assert!(!res.headers.has("Content-Encoding"));
+        assert!(!res.headers.has("Vary"));
+
+        let res = get(&router, "/text", "br");
+        assert!(!res.headers.has("Content-Encoding"));
+        assert_eq!(res.body.as_deref(), Some(text.as_bytes()));
+    }
+
+    #[test]
+    pub fn should_compress_streams() {
+        let text = "hello world ".repeat(10_000);
+        let mut router = Router::new();
+        let body = text.clone();
+
+        router.layer(Compress::new()).get("/large", move || {
+            let mut res = Response::<Bytes>::new();
+            res.header("Content-Type", "text/plain")
+                .header("Content-Length", &body.len().to_string())
+                .stream(io::Cursor::new(body.clone()));
+            return res;
+        });
+
+        let mut res = get(&router, "/large", "gzip");
+        assert!(text.len() > 64 * 1024);
+        assert_eq!(
+            res.headers.get("Content-Encoding").unwrap().to_string(),
+            "gzip"
+        );
+        assert!(!res.headers.has("Content-Length"));
+
+        let mut decoded = String::new();
+        GzDecoder::new(res.stream.take().unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+    }
+}
@@ -0,0 +1,403 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use cube_url::template::{Template, Tree};
+
+use crate::{
+    HttpError, Method, Status,
+    server::{IntoResponse, Request, Response, router::Layer},
+};
+
+/// Cors
+///
+/// answers preflight `OPTIONS` requests and adds the
+/// `Access-Control-*` headers to requests from allowed
+/// origins, cross-origin requests from other origins
+/// are answered with `403`, add it to the `Router` so
+/// preflight requests reach it before any route is matched
+///
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Guides/CORS
+///
+/// Example
+/// -------
+/// `let mut cors = Cors::new();`
+/// `cors.origin("https://*.example.com").credentials(true);`
+/// `router.layer(cors);`
+pub struct Cors {
+    any: bool,
+    origins: Tree<usize>,
+    methods: Vec<Method>,
+    headers: Vec<String>,
+    expose: Vec<String>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+impl Cors {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            any: false,
+            origins: Tree::new(),
+            methods: vec![
+                Method::Get,
+                Method::Head,
+                Method::Post,
+                Method::Put,
+                Method::Patch,
+                Method::Delete,
+            ],
+            headers: vec![String::from("Content-Type")],
+            expose: vec![],
+            credentials: false,
+            max_age: None,
+        };
+    }
+
+    /// allow every origin, without credentials
+    pub fn any() -> Self {
+        let mut cors = Self::new();
+        cors.any = true;
+        return cors;
+    }
+
+    /// allow the origins matching a template,
+    /// such as `https://*.example.com`
+    pub fn origin(&mut self, pattern: &str) -> &mut Self {
+        let template = Template::parse(pattern).expect("[cube::http::cors] => invalid origin");
+        self.origins.insert(&template, 0);
+        return self;
+    }
+
+    /// the methods allowed in preflight requests
+    pub fn methods(&mut self, methods: &[Method]) -> &mut Self {
+        self.methods = methods.to_vec();
+        return self;
+    }
+
+    /// the request headers allowed in preflight
+    /// requests, `*` allows any header
+    pub fn headers(&mut self, headers: &[&str]) -> &mut Self {
+        self.headers = headers.iter().map(|v| v.to_string()).collect();
+        return self;
+    }
+
+    /// the response headers scripts can read
+    pub fn expose(&mut self, headers: &[&str]) -> &mut Self {
+        self.expose = headers.iter().map(|v| v.to_string()).collect();
+        return self;
+    }
+
+    /// allow cookies and the `Authorization` header, which
+    /// would let any site act as the user with `Cors::any`
+    pub fn credentials(&mut self, credentials: bool) -> &mut Self {
+        assert!(
+            !(credentials && self.any),
+            "[cube::http::cors] => credentials can't be allowed for any origin"
+        );
+
+        self.credentials = credentials;
+        return self;
+    }
+
+    /// how long preflight responses can be cached
+    pub fn max_age(&mut self, max_age: Duration) -> &mut Self {
+        self.max_age = Some(max_age);
+        return self;
+    }
+
+    pub fn is_allowed(&self, origin: &str) -> bool {
+        return self.any || self.origins.find(origin).is_some();
+    }
+
+    fn origin_of<T>(req: &Request<T>) -> Option<String> {
+        return req.headers.get("Origin").map(|v| v.to_string());
+    }
+
+    /// browsers send `Origin` with same-origin requests too, such
+    /// as a form `POST`, only the host and port are compared since
+    /// the url is built with `http` behind a proxy that ends tls
+    fn is_same_origin<T>(req: &Request<T>, origin: &str) -> bool {
+        let authority = origin.split_once("://").map_or(origin, |(_, v)| v);
+        return authority.eq_ignore_ascii_case(req.url.host());
+    }
+
+    /// the preflight response, `403` when the
+    /// method or a header is not allowed
+    fn preflight(&self, req: &Request<Bytes>, method: &str, origin: &str) -> Response<Bytes> {
+        let method = Method::try_from(method.trim()).ok();
+
+        if !method.is_some_and(|v| self.methods.contains(&v)) {
+            return self.forbidden("method not allowed");
+        }
+
+        let requested = req
+            .headers
+            .get("Access-Control-Request-Headers")
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+
+        let any_header = self.headers.iter().any(|v| v == "*");
+        let headers = requested
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+
+        if !any_header
+            && !headers
+                .iter()
+                .all(|v| self.headers.iter().any(|h| h.eq_ignore_ascii_case(v)))
+        {
+            return self.forbidden("header not allowed");
+        }
+
+        let methods = self
+            .methods
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut res = Response::<Bytes>::new();
+        res.status(Status::NoContent)
+            .header("Access-Control-Allow-Methods", &methods)
+            .vary("Access-Control-Request-Method")
+            .vary("Access-Control-Request-Headers");
+
+        if !headers.is_empty() {
+            let allowed = match any_header {
+                true => headers.join(", "),
+                false => self.headers.join(", "),
+            };
+
+            res.header("Access-Control-Allow-Headers", &allowed);
+        }
+
+        if let Some(max_age) = self.max_age {
+            res.header("Access-Control-Max-Age", &max_age.as_secs().to_string());
+        }
+
+        self.allow(origin, &mut res);
+        return res;
+    }
+
+    /// set the headers every response to an allowed origin gets
+    fn allow(&self, origin: &str, res: &mut Response<Bytes>) {
+        if self.any {
+            res.header("Access-Control-Allow-Origin", "*");
+        } else {
+            res.header("Access-Control-Allow-Origin", origin)
+                .vary("Origin");
+        }
+
+        if self.credentials {
+            res.header("Access-Control-Allow-Credentials", "true");
+        }
+    }
+
+    /// the `403` for a cross-origin request, which varies on
+    /// `Origin` like every other response to the same url
+    fn forbidden(&self, message: &str) -> Response<Bytes> {
+        let mut res = HttpError::new(Status::Forbidden)
+            .message(message)
+            .into_response();
+
+        if !self.any {
+            res.vary("Origin");
+        }
+
+        return res;
+    }
+}
+
+impl Layer for Cors {
+    fn before(&self, req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        let Some(origin) = Self::origin_of(req) else {
+            return Ok(());
+        };
+
+        if !self.is_allowed(&origin) && !Self::is_same_origin(req, &origin) {
+            return Err(self.forbidden("origin not allowed"));
+        }
+
+        let method = req
+            .headers
+            .get("Access-Control-Request-Method")
+            .map(|v| v.to_string());
+
+        if req.method == Method::Options
+            && let Some(method) = method
+        {
+            return Err(self.preflight(req, &method, &origin));
+        }
+
+        return Ok(());
+    }
+
+    fn after(&self, req: &Request<()>, res: &mut Response<Bytes>) {
+        // whether the origin is echoed depends on `Origin`, so a
+        // shared cache must not reuse a response for another one
+        if !self.any {
+            res.vary("Origin");
+        }
+
+        let Some(origin) = Self::origin_of(req) else {
+            return;
+        };
+
+        if !self.is_allowed(&origin) {
+            return;
+        }
+
+        self.allow(&origin, res);
+
+        if !self.expose.is_empty() {
+            res.header("Access-Control-Expose-Headers", &self.expose.join(", "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Header, Headers, Method, Status,
+        server::{Extensions, Request, Response, router::Router},
+    };
+
+    use super::Cors;
+
+    fn request(method: Method, headers: &[(&str, &str)]) -> Request<Bytes> {
+        let mut map = Headers::new();
+
+        for (name, value) in headers {
+            map.set(name, &Header::from(*value));
+        }
+
+        return Request {
+            method,
+            url: Url::parse("http://localhost/users").unwrap(),
+            headers: map,
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        };
+    }
+
+    fn header(res: &Response<Bytes>, name: &str) -> Option<String> {
+        return res.headers.get(name).map(Header::to_string);
+    }
+
+    fn router(cors: Cors) -> Router {
+        let mut router = Router::new();
+        router.layer(cors).get("/users", || "users");
+        return router;
+    }
+
+    #[test]
+    pub fn should_preflight() {
+        let mut cors = Cors::new();
+        cors.origin("https://*.example.com")
+            .headers(&["Content-Type", "X-Request-Id"])
+            .credentials(true)
+            .max_age(Duration::from_secs(600));
+
+        let router = router(cors);
+        let res = router.dispatch(request(
+            Method::Options,
+            &[
+                ("Origin", "https://app.example.com"),
+                ("Access-Control-Request-Method", "POST"),
+                (
+                    "Access-Control-Request-Headers",
+                    "content-type, x-request-id",
+                ),
+            ],
+        ));
+
+        assert_eq!(res.status, Status::NoContent);
+        assert_eq!(
+            header(&res, "Access-Control-Allow-Origin").as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            header(&res, "Access-Control-Allow-Headers").as_deref(),
+            Some("Content-Type, X-Request-Id")
+        );
+        assert_eq!(
+            header(&res, "Access-Control-Allow-Credentials").as_deref(),
+            Some("true")
+        );
+        assert_eq!(
+            header(&res, "Access-Control-Max-Age").as_deref(),
+            Some("600")
+        );
+
+        let res = router.dispatch(request(
+            Method::Options,
+            &[
+                ("Origin", "https://app.example.com"),
+                ("Access-Control-Request-Method", "POST"),
+                ("Access-Control-Request-Headers", "x-secret"),
+            ],
+        ));
+
+        assert_eq!(res.status, Status::Forbidden);
+    }
+
+    #[test]
+    pub fn should_allow_origins() {
+        let mut cors = Cors::new();
+        cors.origin("https://example.com").expose(&["X-Request-Id"]);
+
+        let router = router(cors);
+        let res = router.dispatch(request(Method::Get, &[("Origin", "https://example.com")]));
+
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(
+            header(&res, "Access-Control-Allow-Origin").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            header(&res, "Access-Control-Expose-Headers").as_deref(),
+            Some("X-Request-Id")
+        );
+        assert_eq!(header(&res, "Vary").as_deref(), Some("Origin"));
+
+        let res = router.dispatch(request(Method::Get, &[("Origin", "https://evil.com")]));
+        assert_eq!(res.status, Status::Forbidden);
+        assert_eq!(header(&res, "Access-Control-Allow-Origin"), None);
+        assert_eq!(header(&res, "Vary").as_deref(), Some("Origin"));
+
+        // same-origin over tls, the url is built with `http`
+        let res = router.dispatch(request(Method::Get, &[("Origin", "https://localhost")]));
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(header(&res, "Access-Control-Allow-Origin"), None);
+
+        let res = router.dispatch(request(Method::Get, &[("Origin", "http://localhost:8080")]));
+        assert_eq!(res.status, Status::Forbidden);
+
+        let res = router.dispatch(request(Method::Get, &[]));
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(header(&res, "Access-Control-Allow-Origin"), None);
+        assert_eq!(header(&res, "Vary").as_deref(), Some("Origin"));
+
+        let res = self::router(Cors::any())
+            .dispatch(request(Method::Get, &[("Origin", "https://evil.com")]));
+        assert_eq!(
+            header(&res, "Access-Control-Allow-Origin").as_deref(),
+            Some("*")
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn should_refuse_credentials_for_any() {
+        Cors::any().credentials(true);
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use bytes::Bytes;
+use cube_url::template::Template;
+
+use crate::{
+    ContentType, HttpError, Method, Status,
+    server::{
+        IntoResponse, Request, Response,
+        router::{Endpoint, Layer, Layers},
+    },
+};
+
+/// files up to this size are read into the
+/// body, larger ones are streamed
+const MAX_BUFFERED: u64 = 64 * 1024;
+
+/// Files
+///
+/// serves the files under `root` at `{prefix}/*`, answering
+/// directories with their index file and unknown paths with
+/// `404`, or with the fallback file when one is set, paths
+/// that leave `root` are never served
+///
+/// Example
+/// -------
+/// `router.route(Files::new("/assets", "./public"))`
+pub struct Files {
+    path: Template,
+    root: PathBuf,
+    index: Option<String>,
+    fallback: Option<String>,
+    layers: Layers,
+}
+
+impl Files {
+    pub fn new<P: AsRef<Path>>(prefix: &str, root: P) -> Self {
+        let path = format!("{}/*", prefix.trim_end_matches('/'));
+
+        return Self {
+            path: Template::parse(&path).expect("[cube::http::files] => invalid prefix"),
+            root: root.as_ref().to_path_buf(),
+            index: Some(String::from("index.html")),
+            fallback: None,
+            layers: Layers::new(),
+        };
+    }
+
+    /// the file served for a directory,
+    /// `None` answers directories with `404`
+    pub fn index(&mut self, name: Option<&str>) -> &mut Self {
+        self.index = name.map(str::to_string);
+        return self;
+    }
+
+    /// the file, relative to `root`, served for paths that match
+    /// no file, such as the `index.html` of a single page app
+    pub fn fallback(&mut self, name: &str) -> &mut Self {
+        self.fallback = Some(name.to_string());
+        return self;
+    }
+
+    /// add a layer that only wraps these files
+    pub fn layer<L: Layer + 'static>(&mut self, layer: L) -> &mut Self {
+        self.layers.push(layer);
+        return self;
+    }
+
+    /// resolve the request path to a file under `root`
+    #[allow(clippy::result_large_err)]
+    fn resolve(&self, req: &Request<Bytes>) -> Result<PathBuf, Response<Bytes>> {
+        let Some(path) = relative(req.param("*").unwrap_or_default()) else {
+            return Err(not_found());
+        };
+
+        let path = self.root.join(path);
+
+        if path.is_dir() {
+            let Some(index) = &self.index else {
+                return Err(not_found());
+            };
+
+            // relative links in the index resolve
+            // against the directory with a trailing slash
+            if !req.url.path().ends_with('/') {
+                let mut res = Response::<Bytes>::new();
+                res.status(Status::MovedPermanently)
+                    .header("Location", &format!("{}/", req.url.path()));
+                return Err(res);
+            }
+
+            return Ok(path.join(index));
+        }
+
+        return Ok(path);
+    }
+
+    fn serve(&self, path: &Path) -> io::Result<Response<Bytes>> {
+        let root = fs::canonicalize(&self.root)?;
+        let path = fs::canonicalize(path)?;
+
+        // symlinks can still point outside of root
+        if !path.starts_with(&root) {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+
+        let mut file = fs::File::open(&path)?;
+        let meta = file.metadata()?;
+
+        if !meta.is_file() {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+
+        let content_type = path
+            .extension()
+            .and_then(|v| v.to_str())
+            .and_then(ContentType::from_extension)
+            .unwrap_or(ContentType::OctetStream);
+
+        let mut res = Response::<Bytes>::new();
+        res.header("Content-Type", &content_type.to_string())
+            .header("Content-Length", &meta.len().to_string());
+
+        if meta.len() > MAX_BUFFERED {
+            res.stream(file);
+        } else {
+            let mut body = Vec::with_capacity(meta.len() as usize);
+            file.read_to_end(&mut body)?;
+            res.body(Bytes::from(body));
+        }
+
+        return Ok(res);
+    }
+}
+
+impl Endpoint for Files {
+    fn method(&self) -> Option<Method> {
+        return Some(Method::Get);
+    }
+
+    fn path(&self) -> &Template {
+        return &self.path;
+    }
+
+    fn layers(&self) -> &Layers {
+        return &self.layers;
+    }
+
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes> {
+        let path = match self.resolve(&req) {
+            Err(res) => return res,
+            Ok(v) => v,
+        };
+
+        let res = match self.serve(&path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => match &self.fallback {
+                Some(fallback) => self.serve(&self.root.join(fallback)),
+                None => Err(err),
+            },
+            res => res,
+        };
+
+        return match res {
+            Ok(v) => v,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => not_found(),
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                HttpError::new(Status::Forbidden).into_response()
+            }
+            Err(err) => HttpError::new(Status::InternalServerError)
+                .message(&err.to_string())
+                .into_response(),
+        };
+    }
+}
+
+fn not_found() -> Response<Bytes> {
+    return HttpError::new(Status::NotFound).into_response();
+}
+
+/// decode a url path into a relative file path, `None`
+/// when any segment could leave the directory it is in
+fn relative(path: &str) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+
+    for segment in path.split('/').filter(|v| !v.is_empty()) {
+        let segment = decode(segment)?;
+
+        if segment.contains(['/', '\\', '\0']) {
+            return None;
+        }
+
+        let mut components = Path::new(&segment).components();
+
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(v)), None) => out.push(v),
+            _ => return None,
+        };
+    }
+
+    return Some(out);
+}
+
+/// percent decode a path segment,
+/// `None` when it isn't valid `UTF-8`
+fn decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|v| u8::from_str_radix(v, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(v)) => {
+                out.push(v);
+                i += 3;
+            }
+            (v, _) => {
+                out.push(v);
+                i += 1;
+            }
+        };
+    }
+
+    return String::from_utf8(out).ok();
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Headers, Method, Status,
+        server::{Extensions, Request, Response, router::Router},
+    };
+
+    use super::Files;
+
+    fn root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("cube-files-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("public/docs")).unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+        fs::write(root.join("public/index.html"), "<h1>home</h1>").unwrap();
+        fs::write(root.join("public/app.js"), "main()").unwrap();
+        fs::write(root.join("public/docs/index.html"), "<h1>docs</h1>").unwrap();
+        return root;
+    }
+
+    fn get(router: &Router, path: &str) -> Response<Bytes> {
+        return router.dispatch(Request {
+            method: Method::Get,
+            url: Url::parse(&format!("http://localhost{}", path)).unwrap(),
+            headers: Headers::new(),
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        });
+    }
+
+    #[test]
+    pub fn should_serve_files() {
+        let root = root("serve");
+        let mut router = Router::new();
+        router.route(Files::new("/assets", root.join("public")));
+
+        let res = get(&router, "/assets/app.js");
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(res.body.as_deref(), Some(b"main()".as_slice()));
+        assert_eq!(
+            res.headers.get("Content-Type").unwrap().to_string(),
+            "text/javascript; charset=utf-8"
+        );
+
+        let res = get(&router, "/assets/docs/");
+        assert_eq!(res.body.as_deref(), Some(b"<h1>docs</h1>".as_slice()));
+
+        let res = get(&router, "/assets/docs");
+        assert_eq!(res.status, Status::MovedPermanently);
+        assert_eq!(
+            res.headers.get("Location").unwrap().to_string(),
+            "/assets/docs/"
+        );
+
+        assert_eq!(get(&router, "/assets/missing.js").status, Status::NotFound);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    pub fn should_not_traverse() {
+        let root = root("traverse");
+        let mut router = Router::new();
+        router.route(Files::new("/assets", root.join("public")));
+
+        for path in [
+            "/assets/../secret.txt",
+            "/assets/%2e%2e/secret.txt",
+            "/assets/docs/%2E%2E%2F..%2Fsecret.txt",
+            "/assets/..%5Csecret.txt",
+        ] {
+            assert_eq!(get(&router, path).status, Status::NotFound, "{}", path);
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    pub fn should_fall_back() {
+        let root = root("fallback");
+        let mut router = Router::new();
+        let mut files = Files::new("/", root.join("public"));
+        files.fallback("index.html");
+        router.route(files);
+
+        let res = get(&router, "/users/1");
+        assert_eq!(res.status, Status::Ok);
+        assert_eq!(res.body.as_deref(), Some(b"<h1>home</h1>".as_slice()));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use crate::server::{Request, Response};
+
+/// Layer
+///
+/// middleware that wraps route handlers, `before` runs in the
+/// order layers were added and can modify the request or stop
+/// the chain early with its own response, `after` runs in the
+/// reverse order and can modify the response, `expect` runs
+/// before the body of a request sent with `Expect: 100-continue`
+/// and can reject it without the client sending the body
+pub trait Layer: Send + Sync {
+    #[allow(clippy::result_large_err)]
+    fn before(&self, _req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        return Ok(());
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn expect(&self, _req: &Request<()>) -> Result<(), Response<Bytes>> {
+        return Ok(());
+    }
+
+    fn after(&self, _req: &Request<()>, _res: &mut Response<Bytes>) {}
+}
+
+/// an ordered list of layers
+#[derive(Clone, Default)]
+pub struct Layers {
+    items: Vec<Arc<dyn Layer>>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        return Self { items: vec![] };
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.items.len();
+    }
+
+    pub fn push<L: Layer + 'static>(&mut self, layer: L) {
+        self.items.push(Arc::new(layer));
+    }
+
+    /// run `before` of each layer, when one stops the chain
+    /// only the layers that already ran get to see its response
+    #[allow(clippy::result_large_err)]
+    pub fn before(&self, req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        for (i, layer) in self.items.iter().enumerate() {
+            if let Err(mut res) = layer.before(req) {
+                let head = req.head();
+
+                for layer in self.items[..i].iter().rev() {
+                    layer.after(&head, &mut res);
+                }
+
+                return Err(res);
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// run `expect` of each layer until one rejects the request
+    #[allow(clippy::result_large_err)]
+    pub fn expect(&self, req: &Request<()>) -> Result<(), Response<Bytes>> {
+        for layer in self.items.iter() {
+            layer.expect(req)?;
+        }
+
+        return Ok(());
+    }
+
+    pub fn after(&self, req: &Request<()>, res: &mut Response<Bytes>) {
+        for layer in self.items.iter().rev() {
+            layer.after(req, res);
+        }
+    }
+}
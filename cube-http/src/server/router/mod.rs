@@ -1,4 +1,817 @@
 mod route;
 pub use route::*;
 
-pub struct Router {}
+mod layer;
+pub use layer::*;
+
+mod body_limit;
+pub use body_limit::*;
+
+mod files;
+pub use files::*;
+
+mod compress;
+pub use compress::*;
+
+mod cors;
+pub use cors::*;
+
+#[cfg(feature = "tokio")]
+mod async_route;
+#[cfg(feature = "tokio")]
+pub use async_route::*;
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use cube_url::template::{Template, Tree};
+
+use crate::{
+    HttpError, Method, Status,
+    server::{Extensions, Handler, IntoResponse, Request, Response},
+};
+
+#[cfg(feature = "tokio")]
+use crate::server::AsyncHandler;
+
+/// maps an error to the response sent for it
+type Catch = Box<dyn Fn(HttpError) -> Response<Bytes> + Send + Sync>;
+
+pub struct Router {
+    routes: Vec<Arc<dyn Endpoint>>,
+    tree: Tree<usize>,
+    layers: Layers,
+    state: Extensions,
+    catch: Option<Catch>,
+}
+
+impl Router {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            routes: vec![],
+            tree: Tree::new(),
+            layers: Layers::new(),
+            state: Extensions::new(),
+            catch: None,
+        };
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.routes.len();
+    }
+
+    /// register application state that every request
+    /// can borrow, replacing any state of the same type,
+    /// use a `Mutex` or atomics inside `T` to mutate it
+    pub fn state<T: Send + Sync + 'static>(&mut self, value: T) -> &mut Self {
+        self.state.set(value);
+        return self;
+    }
+
+    /// add a layer that wraps every request,
+    /// including those that match no route
+    pub fn layer<L: Layer + 'static>(&mut self, layer: L) -> &mut Self {
+        self.layers.push(layer);
+        return self;
+    }
+
+    /// map every error response, including those for panics,
+    /// unmatched routes and malformed requests, to the response
+    /// returned by `f`, keeping headers such as `Allow`
+    pub fn catch<R: IntoResponse, F: Fn(HttpError) -> R + Send + Sync + 'static>(
+        &mut self,
+        f: F,
+    ) -> &mut Self {
+        self.catch = Some(Box::new(move |err| f(err).into_response()));
+        return self;
+    }
+
+    /// the response sent for an error
+    pub fn error(&self, err: HttpError) -> Response<Bytes> {
+        return self.recover(err.into_response());
+    }
+
+    pub fn route<E: Endpoint + 'static>(&mut self, route: E) -> &mut Self {
+        self.tree.insert(route.path(), self.routes.len());
+        self.routes.push(Arc::new(route));
+        return self;
+    }
+
+    pub fn get<Args: 'static, H: Handler<Args>>(&mut self, path: &str, handler: H) -> &mut Self {
+        return self.on(Method::Get, path, handler);
+    }
+
+    pub fn post<Args: 'static, H: Handler<Args>>(&mut self, path: &str, handler: H) -> &mut Self {
+        return self.on(Method::Post, path, handler);
+    }
+
+    pub fn put<Args: 'static, H: Handler<Args>>(&mut self, path: &str, handler: H) -> &mut Self {
+        return self.on(Method::Put, path, handler);
+    }
+
+    pub fn patch<Args: 'static, H: Handler<Args>>(&mut self, path: &str, handler: H) -> &mut Self {
+        return self.on(Method::Patch, path, handler);
+    }
+
+    pub fn delete<Args: 'static, H: Handler<Args>>(&mut self, path: &str, handler: H) -> &mut Self {
+        return self.on(Method::Delete, path, handler);
+    }
+
+    pub fn on<Args: 'static, H: Handler<Args>>(
+        &mut self,
+        method: Method,
+        path: &str,
+        handler: H,
+    ) -> &mut Self {
+        let template = Template::parse(path).expect("[cube::http::router] => invalid route path");
+        let mut route = Route::new(template, handler);
+        Route::method(&mut route, method);
+        return self.route(route);
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn on_async<Args: 'static, H: AsyncHandler<Args>>(
+        &mut self,
+        method: Method,
+        path: &str,
+        handler: H,
+    ) -> &mut Self {
+        let template = Template::parse(path).expect("[cube::http::router] => invalid route path");
+        let mut route = AsyncRoute::new(template, handler);
+        AsyncRoute::method(&mut route, method);
+        return self.route(route);
+    }
+
+    /// find the route matching the request and invoke it with
+    /// the captured path params set on the request url, answering
+    /// `404` when no path matches and `405` when the path matches
+    /// but the method does not, all wrapped by the router layers
+    /// and then by the layers of the route, a panic in the route
+    /// is answered with `500`, `HEAD` is answered by the `GET`
+    /// route without its body and `OPTIONS` with the methods the
+    /// path allows
+    pub fn dispatch(&self, req: Request<Bytes>) -> Response<Bytes> {
+        // a panic in a layer or `catch` skips
+        // whatever is left, answering a plain `500`
+        return panic::catch_unwind(AssertUnwindSafe(|| self.handle(req)))
+            .unwrap_or_else(|_| Self::panicked());
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn dispatch_async(&self, req: Request<Bytes>) -> Response<Bytes> {
+        return Self::unwind(self.handle_async(req)).await;
+    }
+
+    fn handle(&self, mut req: Request<Bytes>) -> Response<Bytes> {
+        self.attach_state(&mut req);
+
+        if let Err(res) = self.layers.before(&mut req) {
+            return self.recover(res);
+        }
+
+        let route = self.find(&mut req);
+        let head = req.head();
+        let res = match route {
+            Err(res) => res,
+            Ok(route) => {
+                panic::catch_unwind(AssertUnwindSafe(|| Self::invoke(route.as_ref(), req)))
+                    .unwrap_or_else(|_| Self::panicked())
+            }
+        };
+
+        let mut res = self.recover(res);
+        self.layers.after(&head, &mut res);
+
+        if head.method == Method::Head {
+            Self::strip(&mut res);
+        }
+
+        return res;
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn handle_async(&self, mut req: Request<Bytes>) -> Response<Bytes> {
+        self.attach_state(&mut req);
+
+        if let Err(res) = self.layers.before(&mut req) {
+            return self.recover(res);
+        }
+
+        let route = self.find(&mut req);
+        let head = req.head();
+        let res = match route {
+            Err(res) => res,
+            Ok(route) => Self::unwind(Self::invoke_async(route.clone(), req)).await,
+        };
+
+        let mut res = self.recover(res);
+        self.layers.after(&head, &mut res);
+
+        if head.method == Method::Head {
+            Self::strip(&mut res);
+        }
+
+        return res;
+    }
+
+    /// check a request before its body is read, answering
+    /// unmatched routes with `404` or `405` and letting the
+    /// router and route layers reject it
+    #[allow(clippy::result_large_err)]
+    pub fn expect(&self, req: &Request<Bytes>) -> Result<(), Response<Bytes>> {
+        let mut req = req.clone();
+        self.attach_state(&mut req);
+
+        let result = self.layers.expect(&req.head()).and_then(|()| {
+            let route = self.find(&mut req)?;
+            return route.layers().expect(&req.head());
+        });
+
+        return result.map_err(|res| {
+            let mut res = self.recover(res);
+            self.layers.after(&req.head(), &mut res);
+            return res;
+        });
+    }
+
+    /// call the route wrapped by its layers
+    fn invoke(route: &dyn Endpoint, mut req: Request<Bytes>) -> Response<Bytes> {
+        if let Err(res) = route.layers().before(&mut req) {
+            return res;
+        }
+
+        let head = req.head();
+        let mut res = route.call(req);
+        route.layers().after(&head, &mut res);
+        return res;
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn invoke_async(route: Arc<dyn Endpoint>, mut req: Request<Bytes>) -> Response<Bytes> {
+        if let Err(res) = route.layers().before(&mut req) {
+            return res;
+        }
+
+        let head = req.head();
+        let mut res = route.clone().call_async(req).await;
+        route.layers().after(&head, &mut res);
+        return res;
+    }
+
+    /// run the future, answering `500` when it panics
+    #[cfg(feature = "tokio")]
+    async fn unwind<F: Future<Output = Response<Bytes>>>(future: F) -> Response<Bytes> {
+        let mut future = std::pin::pin!(future);
+        return std::future::poll_fn(|cx| {
+            return match panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+                Err(_) => std::task::Poll::Ready(Self::panicked()),
+                Ok(v) => v,
+            };
+        })
+        .await;
+    }
+
+    fn panicked() -> Response<Bytes> {
+        return HttpError::new(Status::InternalServerError).into_response();
+    }
+
+    /// map an error response with `catch`, if any
+    fn recover(&self, mut res: Response<Bytes>) -> Response<Bytes> {
+        let (Some(catch), Some(err)) = (&self.catch, res.error.take()) else {
+            return res;
+        };
+
+        let mut mapped = catch(err);
+
+        for (name, value) in res.headers {
+            if !name.eq_ignore_ascii_case("Content-Type") && !mapped.headers.has(&name) {
+                mapped.headers.set(&name, &value);
+            }
+        }
+
+        return mapped;
+    }
+
+    /// add the state under the extensions
+    /// already set on the request
+    fn attach_state(&self, req: &mut Request<Bytes>) {
+        let mut extensions = self.state.clone();
+        extensions.extend(&req.extensions);
+        req.extensions = extensions;
+    }
+
+    /// the route for the request, where `HEAD` falls back to the
+    /// `GET` route, or the response that answers it without one,
+    /// such as `OPTIONS` with the methods the path allows
+    #[allow(clippy::result_large_err)]
+    fn find(&self, req: &mut Request<Bytes>) -> Result<&Arc<dyn Endpoint>, Response<Bytes>> {
+        // the asterisk form only targets the server with `OPTIONS`
+        // https://www.rfc-editor.org/rfc/rfc9112#section-3.2.4
+        if req.url.path() == "*" {
+            if req.method != Method::Options {
+                return Err(HttpError::new(Status::BadRequest).into_response());
+            }
+
+            let methods = self.routes.iter().filter_map(|r| r.method());
+            return Err(Self::options(methods));
+        }
+
+        let path = match req.url.path() {
+            "" => "/",
+            v => v,
+        };
+
+        let method = req.method;
+
+        if let Some(found) = self.tree.find_by(path, |v| self.pick(v, method).is_some())
+            && let Some(route) = self.pick(found.values, method)
+        {
+            *req.url.params_mut() = found.params;
+            return Ok(route);
+        }
+
+        let matches = self.tree.find_all(path);
+
+        if matches.is_empty() {
+            return Err(HttpError::new(Status::NotFound).into_response());
+        }
+
+        let methods = matches
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.routes[*i].method());
+
+        if req.method == Method::Options {
+            return Err(Self::options(methods));
+        }
+
+        let mut res = HttpError::new(Status::MethodNotAllowed).into_response();
+        res.header("Allow", &Self::allow(methods));
+        return Err(res);
+    }
+
+    /// the route of a path for the method, where
+    /// `HEAD` falls back to the `GET` route
+    fn pick(&self, values: &[usize], method: Method) -> Option<&Arc<dyn Endpoint>> {
+        let routes = values.iter().map(|i| &self.routes[*i]);
+        let route = routes
+            .clone()
+            .find(|r| r.method().is_none_or(|m| m == method));
+
+        return route.or_else(|| match method {
+            Method::Head => routes.clone().find(|r| r.method() == Some(Method::Get)),
+            _ => None,
+        });
+    }
+
+    /// answer `OPTIONS` with the allowed methods
+    fn options<I: Iterator<Item = Method>>(methods: I) -> Response<Bytes> {
+        let mut res = Status::NoContent.into_response();
+        res.header("Allow", &Self::allow(methods));
+        return res;
+    }
+
+    /// the `Allow` header for the methods of some routes, along
+    /// with `HEAD` when `GET` is allowed and `OPTIONS`, which
+    /// the router answers itself
+    /// https://www.rfc-editor.org/rfc/rfc9110#field.allow
+    fn allow<I: Iterator<Item = Method>>(methods: I) -> String {
+        let mut allow = Vec::<Method>::new();
+
+        for method in methods.chain([Method::Options]) {
+            if !allow.contains(&method) {
+                allow.push(method);
+            }
+
+            if method == Method::Get && !allow.contains(&Method::Head) {
+                allow.push(Method::Head);
+            }
+        }
+
+        return allow
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+    }
+
+    /// drop the body of a response to `HEAD`, keeping
+    /// the `Content-Length` the body would have had
+    fn strip(res: &mut Response<Bytes>) {
+        if let Some(body) = res.body.take()
+            && !res.headers.has("Content-Length")
+        {
+            res.header("Content-Length", &body.len().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Header, Headers, HttpError, Method, Status,
+        server::{Extensions, Request, Response},
+    };
+
+    fn request(method: Method, url: &str) -> Request<Bytes> {
+        return Request {
+            method,
+            url: Url::parse(url).unwrap(),
+            headers: Headers::new(),
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        };
+    }
+
+    #[test]
+    pub fn should_dispatch() {
+        let mut router = super::Router::new();
+
+        router
+            .get(
+                "/users/{user}",
+                |_: &Request<String>, res: &mut Response<String>| {
+                    res.status(Status::Accepted);
+                },
+            )
+            .post(
+                "/users",
+                |_: &Request<String>, res: &mut Response<String>| {
+                    res.status(Status::Created);
+                },
+            );
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/users/1"));
+        assert_eq!(res.status, Status::Accepted);
+
+        let res = router.dispatch(request(Method::Post, "http://localhost/users"));
+        assert_eq!(res.status, Status::Created);
+    }
+
+    #[test]
+    pub fn should_not_find() {
+        let mut router = super::Router::new();
+        router.get("/users", |_: &Request<String>, _: &mut Response<String>| {});
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/orgs"));
+        assert_eq!(res.status, Status::NotFound);
+    }
+
+    #[test]
+    pub fn should_not_allow() {
+        let mut router = super::Router::new();
+
+        router
+            .get("/users", |_: &Request<String>, _: &mut Response<String>| {})
+            .post("/users", |_: &Request<String>, _: &mut Response<String>| {});
+
+        let res = router.dispatch(request(Method::Delete, "http://localhost/users"));
+        assert_eq!(res.status, Status::MethodNotAllowed);
+        assert_eq!(
+            res.headers.get("Allow").unwrap().to_string(),
+            "GET, HEAD, POST, OPTIONS"
+        );
+    }
+
+    #[test]
+    pub fn should_fall_back_on_method() {
+        let mut router = super::Router::new();
+
+        router
+            .get(
+                "/users/me",
+                |_: &Request<String>, res: &mut Response<String>| {
+                    res.status(Status::Accepted);
+                },
+            )
+            .post(
+                "/users/{user}",
+                |req: &Request<String>, res: &mut Response<String>| {
+                    res.status(Status::Created);
+                    res.body(req.param("user").unwrap().to_string());
+                },
+            );
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/users/me"));
+        assert_eq!(res.status, Status::Accepted);
+
+        let res = router.dispatch(request(Method::Post, "http://localhost/users/me"));
+        assert_eq!(res.status, Status::Created);
+        assert_eq!(res.body.as_deref(), Some(b"me".as_slice()));
+
+        let res = router.dispatch(request(Method::Delete, "http://localhost/users/me"));
+        assert_eq!(res.status, Status::MethodNotAllowed);
+        assert_eq!(
+            res.headers.get("Allow").unwrap().to_string(),
+            "GET, HEAD, POST, OPTIONS"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    pub async fn should_dispatch_in_runtime() {
+        let mut router = super::Router::new();
+        let executor = std::thread::current().id();
+
+        router
+            .on_async(Method::Get, "/async", || async { "async" })
+            .get("/sync", move || {
+                assert_ne!(std::thread::current().id(), executor);
+                return "sync";
+            });
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/async"));
+        assert_eq!(res.body.as_deref(), Some(b"async".as_slice()));
+
+        let res = router
+            .dispatch_async(request(Method::Get, "http://localhost/sync"))
+            .await;
+        assert_eq!(res.body.as_deref(), Some(b"sync".as_slice()));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn should_dispatch_in_worker() {
+        let mut router = super::Router::new();
+        let worker = std::thread::current().id();
+
+        router.on_async(Method::Get, "/async", move || async move {
+            // blocks in place rather than on a thread of its own
+            assert_eq!(std::thread::current().id(), worker);
+            tokio::task::yield_now().await;
+            return "async";
+        });
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/async"));
+        assert_eq!(res.body.as_deref(), Some(b"async".as_slice()));
+    }
+
+    struct Tag(&'static str);
+
+    impl super::Layer for Tag {
+        fn after(&self, _: &Request<()>, res: &mut Response<Bytes>) {
+            let tags = match res.headers.get("X-Tags") {
+                None => self.0.to_string(),
+                Some(v) => format!("{},{}", v, self.0),
+            };
+
+            res.header("X-Tags", &tags);
+        }
+    }
+
+    struct Auth;
+
+    impl super::Layer for Auth {
+        fn before(&self, req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+            if req.headers.has("Authorization") {
+                return Ok(());
+            }
+
+            let mut res = Response::<Bytes>::new();
+            res.status(Status::Unauthorized);
+            return Err(res);
+        }
+    }
+
+    #[test]
+    pub fn should_run_layers() {
+        let mut router = super::Router::new();
+        let mut route = super::Route::new(
+            cube_url::template::Template::parse("/admin").unwrap(),
+            |_: &Request<String>, res: &mut Response<String>| {
+                res.status(Status::Accepted);
+            },
+        );
+
+        super::Route::method(&mut route, Method::Get)
+            .layer(Tag("route"))
+            .layer(Auth);
+
+        router
+            .layer(Tag("outer"))
+            .layer(Tag("inner"))
+            .route(route)
+            .get("/", |_: &Request<String>, _: &mut Response<String>| {});
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/"));
+        assert_eq!(
+            res.headers.get("X-Tags").unwrap().to_string(),
+            "inner,outer"
+        );
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/admin"));
+        assert_eq!(res.status, Status::Unauthorized);
+        assert_eq!(
+            res.headers.get("X-Tags").unwrap().to_string(),
+            "route,inner,outer"
+        );
+
+        let mut req = request(Method::Get, "http://localhost/admin");
+        req.headers
+            .set("Authorization", &crate::Header::Raw(String::from("token")));
+
+        let res = router.dispatch(req);
+        assert_eq!(res.status, Status::Accepted);
+    }
+
+    #[test]
+    pub fn should_share_state() {
+        use std::sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        use crate::server::extract::State;
+
+        let mut router = super::Router::new();
+
+        router
+            .state(AtomicUsize::new(0))
+            .get("/count", |State(count): State<AtomicUsize>| {
+                return (count.fetch_add(1, Ordering::SeqCst) + 1).to_string();
+            });
+
+        let router = Arc::new(router);
+        let threads = (0..4)
+            .map(|_| {
+                let router = router.clone();
+                std::thread::spawn(move || {
+                    router.dispatch(request(Method::Get, "http://localhost/count"));
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/count"));
+        assert_eq!(res.body.unwrap(), "5");
+    }
+
+    #[test]
+    pub fn should_catch_panics() {
+        let mut router = super::Router::new();
+        router
+            .get("/panic", || -> &'static str { panic!("oops") })
+            .layer(Tag("global"));
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/panic"));
+        assert_eq!(res.status, Status::InternalServerError);
+        assert_eq!(res.headers.get("X-Tags").unwrap().to_string(), "global");
+    }
+
+    struct Panic;
+
+    impl super::Layer for Panic {
+        fn after(&self, _: &Request<()>, _: &mut Response<Bytes>) {
+            panic!("oops");
+        }
+    }
+
+    #[test]
+    pub fn should_catch_layer_panics() {
+        let mut router = super::Router::new();
+        router.get("/", || "ok").layer(Panic);
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/"));
+        assert_eq!(res.status, Status::InternalServerError);
+
+        let mut router = super::Router::new();
+        router
+            .get("/users", || HttpError::new(Status::Conflict))
+            .catch(|_: HttpError| -> &'static str { panic!("oops") });
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/users"));
+        assert_eq!(res.status, Status::InternalServerError);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    pub async fn should_catch_layer_panics_async() {
+        let mut router = super::Router::new();
+        router
+            .on_async(Method::Get, "/", || async { "ok" })
+            .layer(Panic);
+
+        let res = router
+            .dispatch_async(request(Method::Get, "http://localhost/"))
+            .await;
+        assert_eq!(res.status, Status::InternalServerError);
+    }
+
+    #[test]
+    pub fn should_map_errors() {
+        let mut router = super::Router::new();
+        router
+            .get("/users", || {
+                HttpError::new(Status::Conflict).message("taken")
+            })
+            .catch(|err: HttpError| (err.status, format!("error: {}", err.status.as_u16())));
+
+        let res = router.dispatch(request(Method::Get, "http://localhost/users"));
+        assert_eq!(res.status, Status::Conflict);
+        assert_eq!(res.body.as_deref(), Some(b"error: 409".as_slice()));
+
+        let res = router.dispatch(request(Method::Post, "http://localhost/users"));
+        assert_eq!(res.status, Status::MethodNotAllowed);
+        assert_eq!(res.body.as_deref(), Some(b"error: 405".as_slice()));
+        assert_eq!(
+            res.headers.get("Allow").unwrap().to_string(),
+            "GET, HEAD, OPTIONS"
+        );
+
+        let res = router.error(HttpError::new(Status::BadRequest));
+        assert_eq!(res.body.as_deref(), Some(b"error: 400".as_slice()));
+    }
+
+    #[test]
+    pub fn should_expect() {
+        let mut router = super::Router::new();
+        let mut route = super::Route::new(
+            cube_url::template::Template::parse("/upload").unwrap(),
+            |_: &Request<String>, res: &mut Response<String>| {
+                res.status(Status::Created);
+            },
+        );
+
+        super::Route::method(&mut route, Method::Post).layer(super::BodyLimit::new(4));
+        router.route(route).layer(Tag("global"));
+
+        let mut req = request(Method::Post, "http://localhost/upload");
+        req.headers
+            .set("Content-Length", &Header::Raw(String::from("4")));
+        assert!(router.expect(&req).is_ok());
+
+        req.headers
+            .set("Content-Length", &Header::Raw(String::from("5")));
+        let res = router.expect(&req).unwrap_err();
+        assert_eq!(res.status, Status::ContentTooLarge);
+        assert_eq!(res.headers.get("X-Tags").unwrap().to_string(), "global");
+
+        let res = router
+            .expect(&request(Method::Post, "http://localhost/missing"))
+            .unwrap_err();
+        assert_eq!(res.status, Status::NotFound);
+
+        req.body = Some(Bytes::from("hello"));
+        let res = router.dispatch(req);
+        assert_eq!(res.status, Status::ContentTooLarge);
+    }
+
+    #[test]
+    pub fn should_answer_head() {
+        let mut router = super::Router::new();
+        router.get("/", || "hello world");
+
+        let res = router.dispatch(request(Method::Head, "http://localhost/"));
+        assert_eq!(res.status, Status::Ok);
+        assert!(res.body.is_none());
+        assert_eq!(res.headers.get("Content-Length").unwrap().to_string(), "11");
+    }
+
+    #[test]
+    pub fn should_answer_options() {
+        let mut router = super::Router::new();
+        router
+            .get("/users", || "users")
+            .post("/users", || Status::Created)
+            .delete("/users/{user}", || Status::NoContent);
+
+        let res = router.dispatch(request(Method::Options, "http://localhost/users"));
+        assert_eq!(res.status, Status::NoContent);
+        assert_eq!(
+            res.headers.get("Allow").unwrap().to_string(),
+            "GET, HEAD, POST, OPTIONS"
+        );
+
+        let mut req = request(Method::Options, "http://localhost/");
+        *req.url.path_mut() = String::from("*");
+
+        let res = router.dispatch(req);
+        assert_eq!(res.status, Status::NoContent);
+        assert_eq!(
+            res.headers.get("Allow").unwrap().to_string(),
+            "GET, HEAD, POST, DELETE, OPTIONS"
+        );
+
+        let mut req = request(Method::Get, "http://localhost/");
+        *req.url.path_mut() = String::from("*");
+
+        let res = router.dispatch(req);
+        assert_eq!(res.status, Status::BadRequest);
+        assert!(!res.headers.has("Allow"));
+
+        let res = router.dispatch(request(Method::Options, "http://localhost/orgs"));
+        assert_eq!(res.status, Status::NotFound);
+    }
+}
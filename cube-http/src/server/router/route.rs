@@ -1,33 +1,37 @@
 use std::marker::PhantomData;
 
+use bytes::Bytes;
 use cube_url::template::Template;
 
 use crate::{
     Method,
-    server::{Request, Response},
+    server::{
+        Handler, Request, Response,
+        router::{Layer, Layers},
+    },
 };
 
-pub struct Route<ReqBody, ResBody, Handler>
-where
-    Handler: Fn(&Request<ReqBody>, &mut Response<ResBody>),
-{
-    __phantom_req_body__: PhantomData<ReqBody>,
-    __phantom_res_body__: PhantomData<ResBody>,
+#[cfg(feature = "tokio")]
+use std::sync::Arc;
+
+#[cfg(feature = "tokio")]
+use crate::{HttpError, Status, server::IntoResponse};
+
+pub struct Route<Args, H: Handler<Args>> {
+    __phantom_args__: PhantomData<fn() -> Args>,
     method: Option<Method>,
     path: Template,
-    handler: Handler,
+    layers: Layers,
+    handler: H,
 }
 
-impl<ReqBody, ResBody, Handler> Route<ReqBody, ResBody, Handler>
-where
-    Handler: Fn(&Request<ReqBody>, &mut Response<ResBody>),
-{
-    pub fn new(path: Template, handler: Handler) -> Self {
+impl<Args, H: Handler<Args>> Route<Args, H> {
+    pub fn new(path: Template, handler: H) -> Self {
         return Self {
-            __phantom_req_body__: PhantomData::<ReqBody>,
-            __phantom_res_body__: PhantomData::<ResBody>,
+            __phantom_args__: PhantomData,
             method: None,
             path,
+            layers: Layers::new(),
             handler,
         };
     }
@@ -37,20 +41,68 @@ where
         return self;
     }
 
-    pub fn is_match(&self, req: &Request<ReqBody>) -> bool {
-        if let Some(method) = self.method {
-            if method != req.method {
-                return false;
-            }
+    /// add a layer that only wraps this route
+    pub fn layer<L: Layer + 'static>(&mut self, layer: L) -> &mut Self {
+        self.layers.push(layer);
+        return self;
+    }
+
+    pub fn is_match<T>(&self, req: &Request<T>) -> bool {
+        if let Some(method) = self.method
+            && method != req.method
+        {
+            return false;
         }
 
-        return match self.path.eval(&req.url.to_string()) {
-            Err(_) => false,
-            Ok(_) => true,
-        };
+        return self.path.eval_path(&req.url).is_ok();
+    }
+
+    pub fn invoke(&self, req: Request<Bytes>) -> Response<Bytes> {
+        return self.handler.call(req);
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// a type erased `Route` that the `Router`
+/// can store and dispatch to
+pub trait Endpoint: Send + Sync + 'static {
+    fn method(&self) -> Option<Method>;
+    fn path(&self) -> &Template;
+    fn layers(&self) -> &Layers;
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes>;
+
+    /// run `call` on the blocking threads of
+    /// the runtime, so it can't stall the executor
+    #[cfg(feature = "tokio")]
+    fn call_async(self: Arc<Self>, req: Request<Bytes>) -> BoxFuture<'static, Response<Bytes>> {
+        return Box::pin(async move {
+            return match tokio::task::spawn_blocking(move || self.call(req)).await {
+                Ok(res) => res,
+                Err(err) => match err.try_into_panic() {
+                    Ok(panic) => std::panic::resume_unwind(panic),
+                    Err(_) => HttpError::new(Status::InternalServerError).into_response(),
+                },
+            };
+        });
+    }
+}
+
+impl<Args: 'static, H: Handler<Args>> Endpoint for Route<Args, H> {
+    fn method(&self) -> Option<Method> {
+        return self.method;
+    }
+
+    fn path(&self) -> &Template {
+        return &self.path;
+    }
+
+    fn layers(&self) -> &Layers {
+        return &self.layers;
     }
 
-    pub fn invoke(&self, req: &Request<ReqBody>, res: &mut Response<ResBody>) {
-        (self.handler)(req, res);
+    fn call(&self, req: Request<Bytes>) -> Response<Bytes> {
+        return self.invoke(req);
     }
 }
@@ -0,0 +1,191 @@
+use std::time::{Duration, SystemTime};
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::server::session::SessionData;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// a random id that can't be guessed
+pub(crate) fn random_id() -> String {
+    let mut buf = [0; 32];
+    getrandom::getrandom(&mut buf).expect("[cube::http::session] => no random source");
+    return BASE64.encode(buf);
+}
+
+/// encode the values with when they expire,
+/// as `{expires}|{key}={value}&...`
+pub(crate) fn encode(data: &SessionData, ttl: Option<Duration>) -> String {
+    let expires = ttl
+        .and_then(|v| {
+            (SystemTime::now() + v)
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .ok()
+        })
+        .map(|v| v.as_secs())
+        .unwrap_or(0);
+
+    let pairs = data
+        .iter()
+        .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    return format!("{}|{}", expires, pairs);
+}
+
+/// decode values made by `encode`,
+/// `None` when they have expired
+pub(crate) fn decode(value: &str) -> Option<SessionData> {
+    let (expires, pairs) = value.split_once('|')?;
+    let expires = expires.parse::<u64>().ok()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+
+    if expires != 0 && expires <= now {
+        return None;
+    }
+
+    let mut data = SessionData::new();
+
+    for pair in pairs.split('&').filter(|v| !v.is_empty()) {
+        let (key, value) = pair.split_once('=')?;
+        data.insert(unescape(key)?, unescape(value)?);
+    }
+
+    return Some(data);
+}
+
+/// sign `value` for the cookie `name` as `{value}.{mac}`
+pub(crate) fn sign(key: &[u8], name: &str, value: &str) -> String {
+    let value = BASE64.encode(value);
+    let mac = mac(key, name, &value).finalize().into_bytes();
+    return format!("{}.{}", value, BASE64.encode(mac));
+}
+
+/// the value signed by `sign`, `None` when the
+/// signature doesn't match
+pub(crate) fn verify(key: &[u8], name: &str, signed: &str) -> Option<String> {
+    let (value, signature) = signed.rsplit_once('.')?;
+    let signature = BASE64.decode(signature).ok()?;
+
+    // compared in constant time
+    mac(key, name, value).verify_slice(&signature).ok()?;
+    return String::from_utf8(BASE64.decode(value).ok()?).ok();
+}
+
+/// encrypt `value` for the cookie `name`
+/// as `{nonce}{ciphertext}`
+pub(crate) fn seal(key: &[u8; 32], name: &str, value: &str) -> String {
+    let mut nonce = [0; 12];
+    getrandom::getrandom(&mut nonce).expect("[cube::http::session] => no random source");
+
+    let cipher = Aes256Gcm::new(key.into());
+    let payload = Payload {
+        msg: value.as_bytes(),
+        aad: name.as_bytes(),
+    };
+
+    let mut out = nonce.to_vec();
+    out.extend(
+        cipher
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .expect("[cube::http::session] => failed to encrypt"),
+    );
+
+    return BASE64.encode(out);
+}
+
+/// the value encrypted by `seal`, `None` when it
+/// was changed or encrypted with another key
+pub(crate) fn open(key: &[u8; 32], name: &str, sealed: &str) -> Option<String> {
+    let bytes = BASE64.decode(sealed).ok()?;
+
+    if bytes.len() < 12 {
+        return None;
+    }
+
+    let (nonce, msg) = bytes.split_at(12);
+    let cipher = Aes256Gcm::new(key.into());
+    let payload = Payload {
+        msg,
+        aad: name.as_bytes(),
+    };
+
+    let value = cipher.decrypt(Nonce::from_slice(nonce), payload).ok()?;
+    return String::from_utf8(value).ok();
+}
+
+/// the mac of a value bound to the cookie name, so it
+/// can't be moved to another cookie signed with the key
+fn mac(key: &[u8], name: &str, value: &str) -> HmacSha256 {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("hmac takes keys of any size");
+    mac.update(name.as_bytes());
+    mac.update(b"=");
+    mac.update(value.as_bytes());
+    return mac;
+}
+
+fn escape(value: &str) -> String {
+    return value
+        .replace('%', "%25")
+        .replace('&', "%26")
+        .replace('=', "%3D")
+        .replace('|', "%7C");
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut out = Vec::<u8>::with_capacity(value.len());
+    let bytes = value.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    return String::from_utf8(out).ok();
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::server::session::SessionData;
+
+    #[test]
+    pub fn should_round_trip() {
+        let mut data = SessionData::new();
+        data.insert(String::from("name"), String::from("a=b&c|100%"));
+
+        let value = super::encode(&data, Some(Duration::from_secs(60)));
+        assert_eq!(super::decode(&value), Some(data.clone()));
+        assert_eq!(super::decode(&super::encode(&data, None)), Some(data));
+        assert_eq!(super::decode("1|a=1"), None);
+
+        let key = [7; 32];
+        let signed = super::sign(&key, "session", &value);
+        assert_eq!(super::verify(&key, "session", &signed), Some(value.clone()));
+        assert_eq!(super::verify(&key, "other", &signed), None);
+        assert_eq!(super::verify(&[8; 32], "session", &signed), None);
+
+        let sealed = super::seal(&key, "session", &value);
+        assert!(!sealed.contains("name"));
+        assert_eq!(super::open(&key, "session", &sealed), Some(value));
+        assert_eq!(super::open(&[8; 32], "session", &sealed), None);
+    }
+}
@@ -0,0 +1,227 @@
+mod store;
+pub use store::*;
+
+mod sessions;
+pub use sessions::*;
+
+mod codec;
+
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bytes::Bytes;
+
+use crate::{
+    HttpError, Status,
+    server::{Request, extract::FromRequest},
+};
+#[cfg(feature = "serde")]
+use cube_core::error::Error;
+
+/// the values of a session, each stored as a string,
+/// or as JSON when set with `Session::set_json`
+pub type SessionData = BTreeMap<String, String>;
+
+#[derive(Debug, Default)]
+struct Inner {
+    id: Option<String>,
+    data: SessionData,
+    changed: bool,
+    rotate: bool,
+    destroyed: bool,
+}
+
+/// Session
+///
+/// the values kept for a client across requests, loaded by
+/// the `Sessions` layer and written back only when changed,
+/// clones share the same values
+///
+/// Example
+/// -------
+/// `session.set("user_id", 1);`
+/// `let id = session.get::<u64>("user_id");`
+/// `session.set_json("cart", &cart)?;`
+/// `let cart = session.get_json::<Cart>("cart");`
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub(crate) fn load(id: Option<String>, data: SessionData) -> Self {
+        return Self {
+            inner: Arc::new(Mutex::new(Inner {
+                id,
+                data,
+                ..Default::default()
+            })),
+        };
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        return self.inner.lock().unwrap_or_else(|err| err.into_inner());
+    }
+
+    /// the id of a session kept in a `SessionStore`,
+    /// `None` until the session is first saved
+    pub fn id(&self) -> Option<String> {
+        return self.lock().id.clone();
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.lock().data.len();
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        return self.lock().data.contains_key(key);
+    }
+
+    /// the value of `key` parsed as `T`, `None` when
+    /// it is missing or fails to parse
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        return self.lock().data.get(key)?.parse::<T>().ok();
+    }
+
+    pub fn set<T: ToString>(&self, key: &str, value: T) {
+        self.insert(key, value.to_string());
+    }
+
+    /// the value of `key` set with `set_json`, `None` when
+    /// it is missing or fails to deserialize as `T`
+    #[cfg(feature = "serde")]
+    pub fn get_json<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        return serde_json::from_str(self.lock().data.get(key)?).ok();
+    }
+
+    /// set `key` to the JSON of `value`, for values
+    /// that don't round trip through a string
+    #[cfg(feature = "serde")]
+    pub fn set_json<T: serde::Serialize>(&self, key: &str, value: &T) -> Result<(), Error> {
+        let value = serde_json::to_string(value)
+            .map_err(|err| Error::from(format!("[cube::http::session] => {}", err)))?;
+
+        self.insert(key, value);
+        return Ok(());
+    }
+
+    fn insert(&self, key: &str, value: String) {
+        let mut inner = self.lock();
+
+        if inner.data.get(key) != Some(&value) {
+            inner.data.insert(key.to_string(), value);
+            inner.changed = true;
+        }
+    }
+
+    pub fn del(&self, key: &str) {
+        let mut inner = self.lock();
+
+        if inner.data.remove(key).is_some() {
+            inner.changed = true;
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+
+        if !inner.data.is_empty() {
+            inner.data.clear();
+            inner.changed = true;
+        }
+    }
+
+    /// give the session a new id when it is written back,
+    /// call it on login so an id known before can't be used
+    pub fn rotate(&self) {
+        let mut inner = self.lock();
+        inner.rotate = true;
+        inner.changed = true;
+    }
+
+    /// remove the session and its cookie, such as on logout
+    pub fn destroy(&self) {
+        let mut inner = self.lock();
+        inner.data.clear();
+        inner.destroyed = true;
+        inner.changed = true;
+    }
+
+    pub fn is_changed(&self) -> bool {
+        return self.lock().changed;
+    }
+
+    pub fn data(&self) -> SessionData {
+        return self.lock().data.clone();
+    }
+}
+
+/// a missing session is a mistake in how the
+/// server was built, so it fails with `500`
+impl FromRequest for Session {
+    fn from_request(req: &Request<Bytes>) -> Result<Self, HttpError> {
+        return match req.extensions.get::<Session>() {
+            Some(v) => Ok(v.clone()),
+            None => Err(HttpError::new(Status::InternalServerError)
+                .message("missing session, add the `Sessions` layer")),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Session;
+
+    #[test]
+    pub fn should_track_changes() {
+        let session = Session::new();
+        assert!(!session.is_changed());
+
+        session.set("user_id", 1);
+        assert!(session.is_changed());
+        assert_eq!(session.get::<u64>("user_id"), Some(1));
+        assert_eq!(session.get::<bool>("user_id"), None);
+
+        let session = Session::load(None, session.data());
+        session.set("user_id", 1);
+        session.del("missing");
+        assert!(!session.is_changed());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn should_hold_json() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Cart {
+            items: Vec<String>,
+        }
+
+        let cart = Cart {
+            items: vec![String::from("apple")],
+        };
+
+        let session = Session::new();
+        session.set_json("cart", &cart).unwrap();
+        assert_eq!(session.get_json::<Cart>("cart"), Some(cart));
+        assert_eq!(session.get_json::<u64>("cart"), None);
+
+        let session = Session::load(None, session.data());
+        session
+            .set_json(
+                "cart",
+                &Cart {
+                    items: vec![String::from("apple")],
+                },
+            )
+            .unwrap();
+        assert!(!session.is_changed());
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+
+use crate::{
+    Cookie, SameSite,
+    server::{
+        Request, Response,
+        router::Layer,
+        session::{Session, SessionStore, codec},
+    },
+};
+
+enum Mode {
+    Signed(Vec<u8>),
+    Encrypted([u8; 32]),
+    Store(Arc<dyn SessionStore>),
+}
+
+/// Sessions
+///
+/// loads the `Session` of each request from a cookie that
+/// is either signed, encrypted or holds the id of a session
+/// in a `SessionStore`, and writes it back when it changed,
+/// handlers get the session with the `Session` extractor
+///
+/// Example
+/// -------
+/// `router.layer(Sessions::store(MemoryStore::new()))`
+pub struct Sessions {
+    mode: Mode,
+    cookie: Cookie,
+    ttl: Option<Duration>,
+}
+
+impl Sessions {
+    fn new(mode: Mode) -> Self {
+        let mut cookie = Cookie::new("session", "");
+        cookie.path("/").http_only(true).same_site(SameSite::Lax);
+
+        return Self {
+            mode,
+            cookie,
+            ttl: Some(Duration::from_secs(24 * 60 * 60)),
+        };
+    }
+
+    /// keep the values in a cookie signed with `HMAC-SHA256`,
+    /// the client can read them but not change them
+    pub fn signed(key: &[u8]) -> Self {
+        assert!(
+            key.len() >= 32,
+            "[cube::http::session] => keys need at least 32 bytes"
+        );
+
+        return Self::new(Mode::Signed(key.to_vec()));
+    }
+
+    /// keep the values in a cookie encrypted with
+    /// `AES-256-GCM`, the client can't read or change them
+    pub fn encrypted(key: [u8; 32]) -> Self {
+        return Self::new(Mode::Encrypted(key));
+    }
+
+    /// keep the values in `store`, sending
+    /// only the session id in the cookie
+    pub fn store<S: SessionStore + 'static>(store: S) -> Self {
+        return Self::new(Mode::Store(Arc::new(store)));
+    }
+
+    /// the name and attributes of the session cookie,
+    /// by default `session` on the path `/`, `HttpOnly`
+    /// and `SameSite=Lax`
+    pub fn cookie(&mut self, cookie: &Cookie) -> &mut Self {
+        self.cookie = cookie.clone();
+        return self;
+    }
+
+    /// how long a session lasts after it last changed,
+    /// `None` keeps it until the browser is closed
+    pub fn ttl(&mut self, ttl: Option<Duration>) -> &mut Self {
+        self.ttl = ttl;
+        return self;
+    }
+
+    fn load(&self, value: &str) -> Session {
+        let name = &self.cookie.name;
+        let data = match &self.mode {
+            Mode::Signed(key) => codec::verify(key, name, value).and_then(|v| codec::decode(&v)),
+            Mode::Encrypted(key) => codec::open(key, name, value).and_then(|v| codec::decode(&v)),
+            Mode::Store(store) => {
+                return match store.load(value) {
+                    None => Session::new(),
+                    Some(data) => Session::load(Some(value.to_string()), data),
+                };
+            }
+        };
+
+        return match data {
+            None => Session::new(),
+            Some(data) => Session::load(None, data),
+        };
+    }
+
+    /// write the session back, removing
+    /// its cookie when there is nothing left
+    fn save(&self, session: &Session, res: &mut Response<Bytes>) {
+        let mut inner = session.lock();
+        let name = &self.cookie.name;
+        let value = match &self.mode {
+            Mode::Signed(_) | Mode::Encrypted(_) if inner.data.is_empty() => None,
+            Mode::Signed(key) => Some(codec::sign(
+                key,
+                name,
+                &codec::encode(&inner.data, self.ttl),
+            )),
+            Mode::Encrypted(key) => Some(codec::seal(
+                key,
+                name,
+                &codec::encode(&inner.data, self.ttl),
+            )),
+            Mode::Store(store) => {
+                if let Some(id) = &inner.id
+                    && (inner.rotate || inner.destroyed)
+                {
+                    store.remove(id);
+                    inner.id = None;
+                }
+
+                match inner.destroyed {
+                    true => None,
+                    false => {
+                        let id = inner.id.get_or_insert_with(codec::random_id).clone();
+                        store.save(&id, &inner.data, self.ttl);
+                        Some(id)
+                    }
+                }
+            }
+        };
+
+        let mut cookie = self.cookie.clone();
+
+        match value {
+            None => {
+                cookie.value = String::new();
+                cookie
+                    .expires(SystemTime::UNIX_EPOCH)
+                    .max_age(Duration::ZERO);
+            }
+            Some(value) => {
+                cookie.value = value;
+
+                if let Some(ttl) = self.ttl {
+                    cookie.max_age(ttl);
+                }
+            }
+        };
+
+        res.cookie(&cookie);
+        inner.changed = false;
+        inner.rotate = false;
+        inner.destroyed = false;
+    }
+}
+
+impl Layer for Sessions {
+    fn before(&self, req: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        let session = match req.cookie(&self.cookie.name) {
+            None => Session::new(),
+            Some(cookie) => self.load(&cookie.value),
+        };
+
+        req.extensions.set(session);
+        return Ok(());
+    }
+
+    fn after(&self, req: &Request<()>, res: &mut Response<Bytes>) {
+        let Some(session) = req.extensions.get::<Session>() else {
+            return;
+        };
+
+        if session.is_changed() {
+            self.save(session, res);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use cube_url::Url;
+
+    use crate::{
+        Header, Headers, Method,
+        server::{
+            Extensions, Request, Response,
+            router::Router,
+            session::{MemoryStore, Session},
+        },
+    };
+
+    use super::Sessions;
+
+    fn router(sessions: Sessions) -> Router {
+        let mut router = Router::new();
+        router
+            .layer(sessions)
+            .get("/login", |session: Session| {
+                session.set("user_id", 1);
+                session.rotate();
+            })
+            .get("/me", |session: Session| {
+                return session.get::<String>("user_id").unwrap_or_default();
+            })
+            .get("/logout", |session: Session| session.destroy());
+
+        return router;
+    }
+
+    fn get(router: &Router, path: &str, cookie: Option<&str>) -> Response<Bytes> {
+        let mut headers = Headers::new();
+
+        if let Some(cookie) = cookie {
+            headers.set("Cookie", &Header::from(cookie));
+        }
+
+        return router.dispatch(Request {
+            method: Method::Get,
+            url: Url::parse(&format!("http://localhost{}", path)).unwrap(),
+            headers,
+            body: None,
+            trailers: Headers::new(),
+            extensions: Extensions::new(),
+        });
+    }
+
+    /// the `name=value` of the session cookie set by the response
+    fn cookie(res: &Response<Bytes>) -> Option<String> {
+        let cookie = res.cookies().into_iter().find(|c| c.name == "session")?;
+        return Some(format!("session={}", cookie.value));
+    }
+
+    fn should_keep(sessions: Sessions) {
+        let router = router(sessions);
+        let login = cookie(&get(&router, "/login", None)).unwrap();
+
+        let res = get(&router, "/me", Some(&login));
+        assert_eq!(res.body.as_deref(), Some(b"1".as_slice()));
+        assert_eq!(cookie(&res), None);
+
+        let res = get(
+            &router,
+            "/me",
+            Some(&login.replace("session=", "session=x")),
+        );
+        assert_eq!(res.body.as_deref(), Some(b"".as_slice()));
+
+        let res = get(&router, "/logout", Some(&login));
+        assert!(res.cookies()[0].is_removal());
+    }
+
+    #[test]
+    pub fn should_sign() {
+        should_keep(Sessions::signed(&[1; 32]));
+    }
+
+    #[test]
+    pub fn should_encrypt() {
+        should_keep(Sessions::encrypted([1; 32]));
+    }
+
+    #[test]
+    pub fn should_store() {
+        let store = Arc::new(MemoryStore::new());
+        should_keep(Sessions::store(store.clone()));
+        assert_eq!(store.len(), 0);
+
+        let router = router(Sessions::store(store.clone()));
+        let first = cookie(&get(&router, "/login", None)).unwrap();
+        let second = cookie(&get(&router, "/login", Some(&first))).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(store.len(), 1);
+
+        let res = get(&router, "/me", Some(&first));
+        assert_eq!(res.body.as_deref(), Some(b"".as_slice()));
+
+        let res = get(&router, "/me", Some(&second));
+        assert_eq!(res.body.as_deref(), Some(b"1".as_slice()));
+    }
+}
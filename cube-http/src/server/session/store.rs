@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::server::session::SessionData;
+
+/// Session Store
+///
+/// keeps the values of sessions by id on the server,
+/// so only the id is sent to the client
+pub trait SessionStore: Send + Sync {
+    /// the values of the session, `None` when
+    /// it doesn't exist or has expired
+    fn load(&self, id: &str) -> Option<SessionData>;
+
+    /// save the values of the session, replacing
+    /// any saved before, until `ttl` has passed
+    fn save(&self, id: &str, data: &SessionData, ttl: Option<Duration>);
+
+    fn remove(&self, id: &str);
+}
+
+/// share a store with the code that created it
+impl<S: SessionStore> SessionStore for Arc<S> {
+    fn load(&self, id: &str) -> Option<SessionData> {
+        return self.as_ref().load(id);
+    }
+
+    fn save(&self, id: &str, data: &SessionData, ttl: Option<Duration>) {
+        self.as_ref().save(id, data, ttl);
+    }
+
+    fn remove(&self, id: &str) {
+        self.as_ref().remove(id);
+    }
+}
+
+#[derive(Debug)]
+struct Items {
+    sessions: HashMap<String, (SessionData, Option<Instant>)>,
+    swept: Instant,
+}
+
+/// Memory Store
+///
+/// a `SessionStore` kept in memory, that loses
+/// its sessions when the server stops
+///
+/// expired sessions are dropped when loaded, and those
+/// never loaded again by a sweep at most once per
+/// `sweep_interval` when a session is saved
+#[derive(Debug)]
+pub struct MemoryStore {
+    items: Mutex<Items>,
+    sweep_interval: Duration,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        return Self {
+            items: Mutex::new(Items {
+                sessions: HashMap::new(),
+                swept: Instant::now(),
+            }),
+            sweep_interval: Duration::from_secs(60),
+        };
+    }
+
+    /// how often expired sessions are swept, by default a minute
+    pub fn sweep_interval(&mut self, sweep_interval: Duration) -> &mut Self {
+        self.sweep_interval = sweep_interval;
+        return self;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Items> {
+        return self.items.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.lock().sessions.len();
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self, id: &str) -> Option<SessionData> {
+        let mut items = self.lock();
+        let (data, expires) = items.sessions.get(id)?;
+
+        if expires.is_some_and(|v| v <= Instant::now()) {
+            items.sessions.remove(id);
+            return None;
+        }
+
+        return Some(data.clone());
+    }
+
+    fn save(&self, id: &str, data: &SessionData, ttl: Option<Duration>) {
+        let now = Instant::now();
+        let mut items = self.lock();
+
+        if now.duration_since(items.swept) >= self.sweep_interval {
+            items
+                .sessions
+                .retain(|_, (_, expires)| !expires.is_some_and(|v| v <= now));
+            items.swept = now;
+        }
+
+        items
+            .sessions
+            .insert(id.to_string(), (data.clone(), ttl.map(|v| now + v)));
+    }
+
+    fn remove(&self, id: &str) {
+        self.lock().sessions.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::server::session::SessionData;
+
+    use super::{MemoryStore, SessionStore};
+
+    #[test]
+    pub fn should_expire() {
+        let store = MemoryStore::new();
+        let mut data = SessionData::new();
+        data.insert(String::from("a"), String::from("1"));
+
+        store.save("live", &data, None);
+        store.save("dead", &data, Some(Duration::ZERO));
+
+        assert_eq!(store.load("live"), Some(data));
+        assert_eq!(store.load("dead"), None);
+        assert_eq!(store.len(), 1);
+
+        store.remove("live");
+        assert_eq!(store.load("live"), None);
+    }
+
+    #[test]
+    pub fn should_sweep() {
+        let mut store = MemoryStore::new();
+        let data = SessionData::new();
+
+        store.save("dead", &data, Some(Duration::ZERO));
+        store.save("live", &data, None);
+        assert_eq!(store.len(), 2);
+
+        store.sweep_interval(Duration::ZERO);
+        store.save("live", &data, None);
+        assert_eq!(store.len(), 1);
+    }
+}
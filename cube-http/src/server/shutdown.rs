@@ -0,0 +1,236 @@
+use std::{
+    io,
+    sync::{
+        Arc, Condvar, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Shutdown Handle
+///
+/// stops a running `Server` from accepting connections,
+/// its open connections close once their in-flight request
+/// is answered and `run` returns when they are all closed
+/// or the shutdown timeout has passed
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    triggered: Arc<AtomicBool>,
+    connections: Arc<AtomicUsize>,
+
+    /// wakes the threads waiting for the shutdown
+    /// to trigger or for a connection to close
+    changed: Arc<(Mutex<()>, Condvar)>,
+
+    #[cfg(feature = "tokio")]
+    notify: Arc<tokio::sync::Notify>,
+}
+
+impl Shutdown {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            triggered: Arc::new(AtomicBool::new(false)),
+            connections: Arc::new(AtomicUsize::new(0)),
+            changed: Arc::new((Mutex::new(()), Condvar::new())),
+
+            #[cfg(feature = "tokio")]
+            notify: Arc::new(tokio::sync::Notify::new()),
+        };
+    }
+
+    pub fn trigger(&self) {
+        self.triggered.store(true, Ordering::SeqCst);
+        self.notify();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        return self.triggered.load(Ordering::SeqCst);
+    }
+
+    /// the number of open connections
+    pub fn connections(&self) -> usize {
+        return self.connections.load(Ordering::SeqCst);
+    }
+
+    /// count a connection as open until
+    /// the returned guard is dropped
+    pub(crate) fn connect(&self) -> Connection {
+        self.connections.fetch_add(1, Ordering::SeqCst);
+        return Connection {
+            shutdown: self.clone(),
+        };
+    }
+
+    /// wake everything waiting on a change
+    fn notify(&self) {
+        let (lock, changed) = &*self.changed;
+        let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+        changed.notify_all();
+
+        #[cfg(feature = "tokio")]
+        self.notify.notify_waiters();
+    }
+
+    /// trigger on `SIGINT` and `SIGTERM`
+    pub(crate) fn listen(&self) -> io::Result<()> {
+        let mut signals = signal_hook::iterator::Signals::new([
+            signal_hook::consts::SIGINT,
+            signal_hook::consts::SIGTERM,
+        ])?;
+        let shutdown = self.clone();
+
+        std::thread::spawn(move || {
+            if signals.forever().next().is_some() {
+                shutdown.trigger();
+            }
+        });
+
+        return Ok(());
+    }
+
+    /// block while `f` holds, checking it again each time the
+    /// shutdown triggers or a connection closes, `false` when
+    /// `timeout` passed first
+    pub(crate) fn wait_while<F: Fn(&Self) -> bool>(&self, timeout: Option<Duration>, f: F) -> bool {
+        let start = Instant::now();
+        let (lock, changed) = &*self.changed;
+        let mut guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+        while f(self) {
+            guard = match timeout {
+                None => changed.wait(guard).unwrap_or_else(|err| err.into_inner()),
+                Some(timeout) => {
+                    let left = timeout.saturating_sub(start.elapsed());
+
+                    if left.is_zero() {
+                        return false;
+                    }
+
+                    changed
+                        .wait_timeout(guard, left)
+                        .unwrap_or_else(|err| err.into_inner())
+                        .0
+                }
+            };
+        }
+
+        return true;
+    }
+
+    /// block until the shutdown is triggered
+    pub(crate) fn wait(&self) {
+        self.wait_while(None, |shutdown| !shutdown.is_triggered());
+    }
+
+    /// block until every connection is closed,
+    /// `false` when `timeout` passed first
+    pub(crate) fn drain(&self, timeout: Option<Duration>) -> bool {
+        return self.wait_while(timeout, |shutdown| shutdown.connections() > 0);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Shutdown {
+    /// wait until the shutdown is triggered
+    pub async fn wait_async(&self) {
+        self.wait_while_async(None, |shutdown| !shutdown.is_triggered())
+            .await;
+    }
+
+    /// trigger on `SIGINT` and `SIGTERM`
+    pub(crate) fn listen_async(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        let shutdown = self.clone();
+
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {},
+                _ = terminate.recv() => {},
+            };
+
+            #[cfg(not(unix))]
+            let _ = tokio::signal::ctrl_c().await;
+
+            shutdown.trigger();
+        });
+
+        return Ok(());
+    }
+
+    /// the async counterpart of `wait_while`
+    pub(crate) async fn wait_while_async<F: Fn(&Self) -> bool>(
+        &self,
+        timeout: Option<Duration>,
+        f: F,
+    ) -> bool {
+        let at = timeout.map(|v| tokio::time::Instant::now() + v);
+
+        loop {
+            // registered before checking, so a change
+            // in between still wakes it
+            let notified = self.notify.notified();
+
+            if !f(self) {
+                return true;
+            }
+
+            match at {
+                None => notified.await,
+                Some(at) => {
+                    if tokio::time::timeout_at(at, notified).await.is_err() {
+                        return false;
+                    }
+                }
+            };
+        }
+    }
+
+    pub(crate) async fn drain_async(&self, timeout: Option<Duration>) -> bool {
+        return self
+            .wait_while_async(timeout, |shutdown| shutdown.connections() > 0)
+            .await;
+    }
+}
+
+/// an open connection counted by `Shutdown`
+pub(crate) struct Connection {
+    shutdown: Shutdown,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.shutdown.connections.fetch_sub(1, Ordering::SeqCst);
+        self.shutdown.notify();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::Shutdown;
+
+    #[test]
+    pub fn should_drain() {
+        let shutdown = Shutdown::new();
+        let connection = shutdown.connect();
+        assert_eq!(shutdown.connections(), 1);
+        assert!(!shutdown.drain(Some(Duration::from_millis(10))));
+
+        let handle = shutdown.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            handle.trigger();
+            drop(connection);
+        });
+
+        shutdown.wait();
+        assert!(shutdown.is_triggered());
+        assert!(shutdown.drain(None));
+        assert_eq!(shutdown.connections(), 0);
+    }
+}
@@ -0,0 +1,28 @@
+use std::{fmt, io};
+
+/// a response body that is read while it is written to
+/// the connection, for bodies too large to hold in memory
+/// or whose length isn't known in advance
+pub struct Stream {
+    inner: Box<dyn io::Read + Send>,
+}
+
+impl Stream {
+    pub fn new<R: io::Read + Send + 'static>(reader: R) -> Self {
+        return Self {
+            inner: Box::new(reader),
+        };
+    }
+}
+
+impl io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        return self.inner.read(buf);
+    }
+}
+
+impl fmt::Debug for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Stream");
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 mod protocol;
 pub use protocol::*;
 
@@ -51,6 +53,7 @@ impl Url {
         return format!("{}://{}", self.protocol, self.host);
     }
 
+    #[allow(clippy::manual_map)]
     pub fn port(&self) -> Option<u16> {
         return match self.port {
             None => None,
@@ -62,10 +65,18 @@ impl Url {
         return &self.path;
     }
 
+    pub fn path_mut(&mut self) -> &mut String {
+        return &mut self.path;
+    }
+
     pub fn params(&self) -> &Params {
         return &self.params;
     }
 
+    pub fn params_mut(&mut self) -> &mut Params {
+        return &mut self.params;
+    }
+
     pub fn query(&self) -> &Query {
         return &self.query;
     }
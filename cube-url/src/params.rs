@@ -11,12 +11,14 @@ pub struct Params {
 }
 
 impl Params {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         return Self {
             data: BTreeMap::new(),
         };
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         return self.data.len();
     }
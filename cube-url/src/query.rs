@@ -13,12 +13,14 @@ pub struct Query {
 }
 
 impl Query {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         return Self {
             data: BTreeMap::new(),
         };
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         return self.data.len();
     }
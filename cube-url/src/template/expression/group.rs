@@ -2,7 +2,10 @@ use std::fmt;
 
 use cube_core::{bytes::Scanner, error::Error};
 
-use crate::{Url, template::Expression};
+use crate::{
+    Url,
+    template::{Expression, Segment},
+};
 
 /// Template Group
 ///
@@ -40,6 +43,16 @@ impl Group {
         return Ok(());
     }
 
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        let mut patterns = vec![vec![]];
+
+        for expr in self.0.iter() {
+            patterns = Segment::product(&patterns, &expr.compile());
+        }
+
+        return patterns;
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
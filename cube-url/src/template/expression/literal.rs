@@ -4,7 +4,7 @@ use cube_core::{bytes::Scanner, error::Error};
 
 use crate::{
     Url,
-    template::{Expression, Token},
+    template::{Expression, Segment, Token},
 };
 
 /// Template Literal
@@ -29,6 +29,7 @@ impl Literal {
         return self.0.end();
     }
 
+    #[allow(clippy::to_string_in_format_args)]
     pub fn eval(&self, scan: &mut Scanner<'_>, url: &mut Url) -> Result<(), Error> {
         while !scan.is_eof() && scan.count() < self.0.len() {
             scan.next();
@@ -48,6 +49,10 @@ impl Literal {
         return Ok(());
     }
 
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        return vec![vec![Segment::Static(self.0.to_string())]];
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
@@ -4,7 +4,7 @@ use cube_core::{bytes::Scanner, error::Error};
 
 use crate::{
     Url,
-    template::{Expression, Literal, Pipe},
+    template::{Expression, Literal, Pipe, Segment},
 };
 
 /// Template Logical
@@ -64,6 +64,12 @@ impl Logical {
         )));
     }
 
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        let mut patterns = self.left.compile();
+        patterns.extend(self.right.compile());
+        return patterns;
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
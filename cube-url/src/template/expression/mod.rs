@@ -1,3 +1,5 @@
+#![allow(clippy::match_like_matches_macro)]
+
 mod var;
 pub(crate) use var::*;
 
@@ -17,7 +19,7 @@ use std::fmt;
 
 use cube_core::{bytes::Scanner, error::Error};
 
-use crate::Url;
+use crate::{Url, template::Segment};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -99,6 +101,18 @@ impl Expression {
         };
     }
 
+    /// compile the expression into every linear
+    /// pattern of segments it can match
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        return match self {
+            Self::Group(v) => v.compile(),
+            Self::Literal(v) => v.compile(),
+            Self::Logical(v) => v.compile(),
+            Self::Var(v) => v.compile(),
+            Self::Wildcard(v) => v.compile(),
+        };
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
@@ -4,7 +4,7 @@ use cube_core::{bytes::Scanner, error::Error};
 
 use crate::{
     Url,
-    template::{Expression, Ident, Wildcard},
+    template::{Expression, Ident, Segment, Wildcard},
 };
 
 /// Template Variable
@@ -61,6 +61,21 @@ impl Var {
         return Ok(());
     }
 
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        let mut patterns = match &self.left {
+            None => vec![vec![]],
+            Some(v) => v.compile(),
+        };
+
+        patterns = Segment::product(&patterns, &[vec![Segment::Param(self.name.name.clone())]]);
+
+        if let Some(right) = &self.right {
+            patterns = Segment::product(&patterns, &right.compile());
+        }
+
+        return patterns;
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
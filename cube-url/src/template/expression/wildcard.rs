@@ -4,7 +4,7 @@ use cube_core::{bytes::Scanner, error::Error};
 
 use crate::{
     Url,
-    template::{Asterisk, Expression, Ident, Logical},
+    template::{Asterisk, Expression, Ident, Logical, Segment},
 };
 
 /// Template Wildcard
@@ -59,6 +59,21 @@ impl Wildcard {
         return Ok(());
     }
 
+    pub fn compile(&self) -> Vec<Vec<Segment>> {
+        let mut patterns = match &self.left {
+            None => vec![vec![]],
+            Some(v) => v.compile(),
+        };
+
+        patterns = Segment::product(&patterns, &[vec![Segment::Wildcard]]);
+
+        if let Some(right) = &self.right {
+            patterns = Segment::product(&patterns, &right.compile());
+        }
+
+        return patterns;
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
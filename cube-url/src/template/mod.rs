@@ -8,6 +8,9 @@ pub(crate) use token::*;
 mod expression;
 pub(crate) use expression::*;
 
+mod tree;
+pub use tree::*;
+
 use cube_core::{bytes::Scanner, error::Error};
 
 use crate::Url;
@@ -40,6 +43,43 @@ impl Template {
         return Ok(uri);
     }
 
+    /// evaluate the template against the path of
+    /// a parsed url, the whole path must be consumed
+    pub fn eval_path(&self, url: &Url) -> Result<Url, Error> {
+        let mut uri = url.clone();
+        let path = match url.path() {
+            "" => "/",
+            v => v,
+        };
+
+        let mut scan = Scanner::from(path);
+
+        for expr in self.0.iter() {
+            expr.eval(&mut scan, &mut uri)?;
+        }
+
+        if !scan.is_eof() {
+            return Err(Error::from(format!(
+                "[cube::url::template] => unexpected '{}'",
+                &path[scan.left()..]
+            )));
+        }
+
+        return Ok(uri);
+    }
+
+    /// compile the template into every linear
+    /// pattern of segments it can match
+    pub(crate) fn compile(&self) -> Vec<Vec<Segment>> {
+        let mut patterns = vec![vec![]];
+
+        for expr in self.0.iter() {
+            patterns = Segment::product(&patterns, &expr.compile());
+        }
+
+        return patterns.into_iter().map(Segment::normalize).collect();
+    }
+
     #[cfg(feature = "serde")]
     pub fn to_json(&self) -> String {
         return serde_json::to_string_pretty(self).unwrap();
@@ -145,4 +185,25 @@ mod test {
         assert_eq!(url.params.get("user").unwrap(), "1234");
         assert_eq!(url.params.get("org_id").unwrap(), "myorgid");
     }
+
+    #[test]
+    pub fn should_evaluate_path() {
+        let template = super::Template::parse("/(user|users)/{user}").unwrap();
+        let url = crate::Url::parse("http://localhost:3000/users/1234").unwrap();
+
+        assert_eq!(
+            template
+                .eval_path(&url)
+                .unwrap()
+                .params
+                .get("user")
+                .unwrap(),
+            "1234"
+        );
+
+        let url = crate::Url::parse("http://localhost:3000/users/1234/orgs").unwrap();
+        let template = super::Template::parse("/users").unwrap();
+
+        assert!(template.eval_path(&url).is_err());
+    }
 }
@@ -0,0 +1,161 @@
+mod node;
+pub(crate) use node::*;
+
+mod segment;
+pub(crate) use segment::*;
+
+use crate::{Params, template::Template};
+
+/// Template Tree
+///
+/// a radix tree compiled from templates, lookups try static
+/// segments before params and params before wildcards, going
+/// back to the next branch when one fails to match, so paths
+/// that many params or wildcards could split take longer
+///
+/// Example
+/// -------
+/// `/users/me` > `/users/{user}` > `/users/*`
+#[derive(Debug, Clone)]
+pub struct Tree<T> {
+    root: Node<T>,
+}
+
+/// the values stored for the matched template
+/// and the params captured from the path
+#[derive(Debug, Clone)]
+pub struct Match<'a, T> {
+    pub values: &'a [T],
+    pub params: Params,
+}
+
+impl<T: Clone> Tree<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self { root: Node::new() };
+    }
+
+    pub fn insert(&mut self, template: &Template, value: T) {
+        for pattern in template.compile() {
+            self.root.insert(&pattern, value.clone());
+        }
+    }
+
+    pub fn find(&self, path: &str) -> Option<Match<'_, T>> {
+        return self.find_by(path, |_| true);
+    }
+
+    /// the highest priority match whose values are accepted by
+    /// `accept`, falling back to lower priority matches such as
+    /// `/users/{user}` when `/users/me` is rejected
+    pub fn find_by<F: FnMut(&[T]) -> bool>(
+        &self,
+        path: &str,
+        mut accept: F,
+    ) -> Option<Match<'_, T>> {
+        let mut captures = Vec::<(String, String)>::new();
+        let node = self.root.find(path, &mut captures, &mut |v| accept(v))?;
+        let mut params = Params::new();
+
+        for (name, value) in captures {
+            params.set(&name, &value);
+        }
+
+        return Some(Match {
+            values: node.values(),
+            params,
+        });
+    }
+
+    /// the values of every template matching
+    /// the path, highest priority first
+    pub fn find_all(&self, path: &str) -> Vec<&[T]> {
+        let mut all = Vec::<&[T]>::new();
+        self.root.find(path, &mut vec![], &mut |v| {
+            all.push(v);
+            return false;
+        });
+
+        return all;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::template::Template;
+
+    fn tree(paths: &[&str]) -> super::Tree<usize> {
+        let mut tree = super::Tree::new();
+
+        for (i, path) in paths.iter().enumerate() {
+            tree.insert(&Template::parse(path).unwrap(), i);
+        }
+
+        return tree;
+    }
+
+    #[test]
+    pub fn should_find() {
+        let tree = tree(&[
+            "/",
+            "/users",
+            "/users/{user}",
+            "/users/{user}/orgs/{org_id}",
+        ]);
+
+        assert_eq!(tree.find("/").unwrap().values, &[0]);
+        assert_eq!(tree.find("/users").unwrap().values, &[1]);
+
+        let found = tree.find("/users/1").unwrap();
+        assert_eq!(found.values, &[2]);
+        assert_eq!(found.params.get("user").unwrap(), "1");
+
+        let found = tree.find("/users/1/orgs/test").unwrap();
+        assert_eq!(found.values, &[3]);
+        assert_eq!(found.params.get("user").unwrap(), "1");
+        assert_eq!(found.params.get("org_id").unwrap(), "test");
+
+        assert!(tree.find("/users/1/orgs").is_none());
+        assert!(tree.find("/orgs").is_none());
+    }
+
+    #[test]
+    pub fn should_prioritize() {
+        let tree = tree(&["/files/*", "/files/{name}", "/files/readme"]);
+
+        assert_eq!(tree.find("/files/readme").unwrap().values, &[2]);
+        assert_eq!(tree.find("/files/test").unwrap().values, &[1]);
+
+        let found = tree.find("/files/a/b/c").unwrap();
+        assert_eq!(found.values, &[0]);
+        assert_eq!(found.params.get("*").unwrap(), "a/b/c");
+    }
+
+    #[test]
+    pub fn should_find_groups() {
+        let tree = tree(&["/(user|users)/{user}", "/files/{name}.json"]);
+
+        assert_eq!(tree.find("/user/1").unwrap().values, &[0]);
+        assert_eq!(tree.find("/users/1").unwrap().values, &[0]);
+        assert!(tree.find("/people/1").is_none());
+
+        let found = tree.find("/files/test.json").unwrap();
+        assert_eq!(found.params.get("name").unwrap(), "test");
+    }
+
+    #[test]
+    pub fn should_fall_back() {
+        let tree = tree(&["/users/me", "/users/{user}", "/users/*"]);
+
+        let found = tree.find_by("/users/me", |v| v != [0]).unwrap();
+        assert_eq!(found.values, &[1]);
+        assert_eq!(found.params.get("user").unwrap(), "me");
+
+        assert_eq!(
+            tree.find_by("/users/me", |v| v == [2]).unwrap().values,
+            &[2]
+        );
+        assert!(tree.find_by("/users/me", |_| false).is_none());
+        assert_eq!(tree.find_all("/users/me"), vec![&[0][..], &[1], &[2]]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::template::Segment;
+
+/// Tree Node
+///
+/// static children are matched first, then params
+/// and finally the wildcard, each branch backtracks
+/// when it fails to reach a node with values
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    prefix: String,
+    name: Option<String>,
+    statics: Vec<Node<T>>,
+    params: Vec<Node<T>>,
+    wildcard: Option<Box<Node<T>>>,
+    values: Vec<T>,
+}
+
+impl<T> Node<T> {
+    pub fn new() -> Self {
+        return Self {
+            prefix: String::new(),
+            name: None,
+            statics: vec![],
+            params: vec![],
+            wildcard: None,
+            values: vec![],
+        };
+    }
+
+    pub fn values(&self) -> &[T] {
+        return &self.values;
+    }
+
+    pub fn insert(&mut self, segments: &[Segment], value: T) {
+        let Some(segment) = segments.first() else {
+            self.values.push(value);
+            return;
+        };
+
+        return match segment {
+            Segment::Static(v) => self.insert_static(v, &segments[1..], value),
+            Segment::Param(name) => {
+                let i = match self
+                    .params
+                    .iter()
+                    .position(|p| p.name.as_ref() == Some(name))
+                {
+                    Some(i) => i,
+                    None => {
+                        let mut node = Self::new();
+                        node.name = Some(name.clone());
+                        self.params.push(node);
+                        self.params.len() - 1
+                    }
+                };
+
+                self.params[i].insert(&segments[1..], value);
+            }
+            Segment::Wildcard => {
+                let node = self.wildcard.get_or_insert_with(|| {
+                    let mut node = Self::new();
+                    node.name = Some(String::from("*"));
+                    Box::new(node)
+                });
+
+                node.insert(&segments[1..], value);
+            }
+        };
+    }
+
+    fn insert_static(&mut self, path: &str, segments: &[Segment], value: T) {
+        if path.is_empty() {
+            return self.insert(segments, value);
+        }
+
+        for child in self.statics.iter_mut() {
+            let mut common = child
+                .prefix
+                .bytes()
+                .zip(path.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            while !path.is_char_boundary(common) {
+                common -= 1;
+            }
+
+            if common == 0 {
+                continue;
+            }
+
+            if common < child.prefix.len() {
+                let mut split = Self::new();
+                split.prefix = child.prefix[common..].to_string();
+                split.statics = std::mem::take(&mut child.statics);
+                split.params = std::mem::take(&mut child.params);
+                split.wildcard = child.wildcard.take();
+                split.values = std::mem::take(&mut child.values);
+                child.prefix.truncate(common);
+                child.statics.push(split);
+            }
+
+            return child.insert_static(&path[common..], segments, value);
+        }
+
+        let mut node = Self::new();
+        node.prefix = path.to_string();
+        node.insert(segments, value);
+        self.statics.push(node);
+    }
+
+    /// find the highest priority node with values accepted by
+    /// `accept` that matches the rest of the path, pushing any
+    /// captures, rejected nodes fall back to lower priority ones
+    pub fn find<'a>(
+        &'a self,
+        path: &str,
+        params: &mut Vec<(String, String)>,
+        accept: &mut dyn FnMut(&'a [T]) -> bool,
+    ) -> Option<&'a Self> {
+        if path.is_empty() && !self.values.is_empty() && accept(&self.values) {
+            return Some(self);
+        }
+
+        for child in self.statics.iter() {
+            if let Some(rest) = path.strip_prefix(child.prefix.as_str()) {
+                if let Some(node) = child.find(rest, params, accept) {
+                    return Some(node);
+                }
+
+                break;
+            }
+        }
+
+        let end = path.find('/').unwrap_or(path.len());
+
+        if end > 0 {
+            for child in self.params.iter() {
+                if let Some(node) = child.capture(path, end, params, accept) {
+                    return Some(node);
+                }
+            }
+        }
+
+        if let Some(child) = &self.wildcard {
+            return child.capture(path, path.len(), params, accept);
+        }
+
+        return None;
+    }
+
+    /// try to capture `path[..i]` into this node for each `i` up to `end`,
+    /// longest first, stopping early only where a static child can follow
+    fn capture<'a>(
+        &'a self,
+        path: &str,
+        end: usize,
+        params: &mut Vec<(String, String)>,
+        accept: &mut dyn FnMut(&'a [T]) -> bool,
+    ) -> Option<&'a Self> {
+        let name = self.name.clone().unwrap_or_default();
+        let min = match self.name.as_deref() {
+            Some("*") => 0,
+            _ => 1,
+        };
+
+        for i in (min..=end).rev() {
+            if !path.is_char_boundary(i) {
+                continue;
+            }
+
+            let rest = &path[i..];
+
+            if i < end && !self.statics.iter().any(|c| rest.starts_with(&c.prefix)) {
+                continue;
+            }
+
+            params.push((name.clone(), path[..i].to_string()));
+
+            if let Some(node) = self.find(rest, params, accept) {
+                return Some(node);
+            }
+
+            params.pop();
+        }
+
+        return None;
+    }
+}
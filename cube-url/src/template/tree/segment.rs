@@ -0,0 +1,52 @@
+/// Template Segment
+///
+/// a single piece of a compiled template pattern,
+/// adjacent static segments are merged when normalized
+///
+/// Example
+/// -------
+/// `/users/{user}/files/*`
+/// =>
+/// `[Static("/users/"), Param("user"), Static("/files/"), Wildcard]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Static(String),
+    Param(String),
+    Wildcard,
+}
+
+impl Segment {
+    /// every combination of a pattern from `left`
+    /// followed by a pattern from `right`
+    pub fn product(left: &[Vec<Self>], right: &[Vec<Self>]) -> Vec<Vec<Self>> {
+        let mut patterns = Vec::<Vec<Self>>::new();
+
+        for a in left {
+            for b in right {
+                let mut pattern = a.clone();
+                pattern.extend(b.iter().cloned());
+                patterns.push(pattern);
+            }
+        }
+
+        return patterns;
+    }
+
+    /// merge adjacent static segments
+    pub fn normalize(pattern: Vec<Self>) -> Vec<Self> {
+        let mut segments = Vec::<Self>::new();
+
+        for segment in pattern {
+            if let Self::Static(value) = &segment
+                && let Some(Self::Static(last)) = segments.last_mut()
+            {
+                last.push_str(value);
+                continue;
+            }
+
+            segments.push(segment);
+        }
+
+        return segments;
+    }
+}
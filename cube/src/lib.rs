@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 pub mod core {
     pub use cube_core::*;
 }
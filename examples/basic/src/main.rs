@@ -1,8 +1,50 @@
+#![allow(clippy::needless_return)]
+
 use std::io;
 
-use cube::http::server::Server;
+use cube::http::{
+    Method, Status,
+    server::{
+        Request, Response, Server,
+        log::{LogFormat, LogWriter},
+        router::Router,
+    },
+};
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
-    return Server::run("0.0.0.0:3000");
+    let mut router = Router::new();
+
+    router
+        .get("/", || "hello world")
+        .get(
+            "/users/{user}",
+            |req: &Request<String>, res: &mut Response<String>| {
+                res.status(Status::Ok)
+                    .body(format!("user {}", req.param("user").unwrap()));
+            },
+        )
+        .post(
+            "/users",
+            |req: &Request<String>, res: &mut Response<String>| {
+                match &req.body {
+                    None => res.status(Status::BadRequest),
+                    Some(_) => res.status(Status::Created),
+                };
+            },
+        )
+        .on_async(
+            Method::Get,
+            "/orgs/{org_id}",
+            |_: Request<String>| async move {
+                let mut res = Response::<String>::new();
+                res.status(Status::Ok);
+                return res;
+            },
+        );
+
+    return Server::new(router)
+        .access_log(LogWriter::stdout(LogFormat::Combined))
+        .run_async("0.0.0.0:3000")
+        .await;
 }